@@ -22,6 +22,39 @@ const AUTHOR_EMAIL: &str = "LordXyn@proton.me";
 const GITHUB_URL: &str = "https://github.com/arcmoonstudios";
 const LICENSE_YEAR: &str = "2024";
 
+// Signature comment styles, rendered into the generated src/utils/langreg.rs
+struct LanguageSpec {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    file_names: &'static [&'static str],
+    comment_start: &'static str,
+    comment_end: &'static str,
+    // Variant name of the generated `HeaderPlacement`
+    placement: &'static str,
+}
+
+const LANGUAGES: &[LanguageSpec] = &[
+    LanguageSpec { name: "rust", extensions: &["rs"], file_names: &[], comment_start: "//", comment_end: "", placement: "FirstLine" },
+    LanguageSpec { name: "javascript", extensions: &["js", "jsx", "mjs", "cjs"], file_names: &[], comment_start: "//", comment_end: "", placement: "AfterShebang" },
+    LanguageSpec { name: "typescript", extensions: &["ts", "tsx"], file_names: &[], comment_start: "//", comment_end: "", placement: "AfterShebang" },
+    LanguageSpec { name: "c", extensions: &["c", "h"], file_names: &[], comment_start: "//", comment_end: "", placement: "FirstLine" },
+    LanguageSpec { name: "cpp", extensions: &["cpp", "cc", "hpp"], file_names: &[], comment_start: "//", comment_end: "", placement: "FirstLine" },
+    LanguageSpec { name: "java", extensions: &["java"], file_names: &[], comment_start: "//", comment_end: "", placement: "FirstLine" },
+    LanguageSpec { name: "css", extensions: &["css", "scss"], file_names: &[], comment_start: "/*", comment_end: "*/", placement: "FirstLine" },
+    LanguageSpec { name: "python", extensions: &["py"], file_names: &[], comment_start: "#", comment_end: "", placement: "AfterShebang" },
+    LanguageSpec { name: "shell", extensions: &["sh", "bash"], file_names: &[], comment_start: "#", comment_end: "", placement: "AfterShebang" },
+    LanguageSpec { name: "ruby", extensions: &["rb"], file_names: &[], comment_start: "#", comment_end: "", placement: "AfterShebang" },
+    LanguageSpec { name: "perl", extensions: &["pl"], file_names: &[], comment_start: "#", comment_end: "", placement: "AfterShebang" },
+    LanguageSpec { name: "yaml", extensions: &["yml", "yaml"], file_names: &[], comment_start: "#", comment_end: "", placement: "FirstLine" },
+    LanguageSpec { name: "markdown", extensions: &["md"], file_names: &[], comment_start: "#", comment_end: "", placement: "FirstLine" },
+    LanguageSpec { name: "docker", extensions: &[], file_names: &["Dockerfile"], comment_start: "#", comment_end: "", placement: "FirstLine" },
+    LanguageSpec { name: "make", extensions: &["mk"], file_names: &["Makefile", "makefile"], comment_start: "#", comment_end: "", placement: "FirstLine" },
+    LanguageSpec { name: "html", extensions: &["html", "htm"], file_names: &[], comment_start: "<!--", comment_end: "-->", placement: "FirstLine" },
+    LanguageSpec { name: "xml", extensions: &["xml"], file_names: &[], comment_start: "<!--", comment_end: "-->", placement: "AfterDeclaration" },
+    LanguageSpec { name: "sql", extensions: &["sql"], file_names: &[], comment_start: "--", comment_end: "", placement: "FirstLine" },
+    LanguageSpec { name: "text", extensions: &["txt"], file_names: &[], comment_start: "--", comment_end: "", placement: "FirstLine" },
+];
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Starting Lord Xyn's Pro Initializer...");
 
@@ -29,7 +62,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     if args.len() < 2 {
         println!("Incorrect number of arguments.");
-        println!("Usage: xynpro <project_name> or xynpro --version");
+        println!("Usage: xynpro <project_name> [--xtask] or xynpro --version");
         return Ok(());
     }
 
@@ -39,11 +72,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             return Ok(());
         }
         project_name => {
+            let options = parse_project_options(&args[2..])?;
             let current_dir = env::current_dir()?;
             let project_path = current_dir.join(project_name);
             println!("Project path: {:?}", project_path);
 
-            create_project_structure(&project_path, project_name)?;
+            create_project_structure(&project_path, project_name, &options)?;
 
             println!("{} project initialized successfully at {:?}!", project_name, project_path);
         }
@@ -52,7 +86,36 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn create_project_structure(project_path: &Path, project_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+// Where signature headers get stamped in the generated project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeaderMode {
+    // build.rs rewrites headers on every build (legacy behaviour)
+    BuildScript,
+    // build.rs only does config codegen; headers are stamped on demand via `cargo xtask stamp`
+    Xtask,
+}
+
+#[derive(Debug, Clone)]
+struct ProjectOptions {
+    header_mode: HeaderMode,
+}
+
+fn parse_project_options(args: &[String]) -> Result<ProjectOptions, Box<dyn std::error::Error>> {
+    let mut options = ProjectOptions {
+        header_mode: HeaderMode::BuildScript,
+    };
+
+    for arg in args {
+        match arg.as_str() {
+            "--xtask" => options.header_mode = HeaderMode::Xtask,
+            unknown => return Err(format!("Unknown option: {}", unknown).into()),
+        }
+    }
+
+    Ok(options)
+}
+
+fn create_project_structure(project_path: &Path, project_name: &str, options: &ProjectOptions) -> Result<(), Box<dyn std::error::Error>> {
     println!("Creating project structure at {:?}", project_path);
     fs::create_dir_all(project_path)?;
 
@@ -66,16 +129,22 @@ fn create_project_structure(project_path: &Path, project_name: &str) -> Result<(
     create_xdocs_files(project_path)?;
     create_utils_files(project_path)?;
     create_omnixtracker_files(project_path)?;
+    create_tests_files(project_path, project_name)?;
+    if options.header_mode == HeaderMode::Xtask {
+        create_xtask_files(project_path)?;
+    }
     create_file(project_path, ".env", &generate_env_content())?;
     create_file(project_path, "LICENSE", &generate_license_content())?;
     create_file(project_path, "src/lib.rs", &generate_lib_rs_content())?;
-    create_file(project_path, "build.rs", &generate_build_rs_content())?;
-    create_file(project_path, "src/main.rs", &generate_main_rs_content())?;
+    create_file(project_path, "build.rs", &generate_build_rs_content(options.header_mode))?;
+    create_file(project_path, "src/main.rs", &generate_main_rs_content(project_name))?;
     create_file(project_path, ".gitignore", &generate_gitignore_content())?;
     create_file(project_path, "README.md", &generate_readme_content(project_name))?;
     create_file(project_path, "src/constants/mod.rs", &generate_constants_content())?;
-    create_file(project_path, "Cargo.toml", &generate_cargo_toml_content(project_name))?;
+    create_file(project_path, "Cargo.toml", &generate_cargo_toml_content(project_name, options.header_mode))?;
     create_file(project_path, "config/prometheus.yml", &generate_prometheus_yml_content(project_name))?;
+    create_file(project_path, "config/languages.toml", &generate_languages_toml_content())?;
+    create_file(project_path, "config/resilience.toml", &generate_resilience_toml_content())?;
 
     Ok(())
 }
@@ -100,6 +169,8 @@ fn create_omnixtracker_files(project_path: &Path) -> Result<(), Box<dyn std::err
     let omnixtracker_path = project_path.join("src/omnixtracker");
     create_file(&omnixtracker_path, "omnixerror.rs", &generate_omnixerror_content())?;
     create_file(&omnixtracker_path, "omnixmetry.rs", &generate_omnixmetry_content())?;
+    create_file(&omnixtracker_path, "omnixlimit.rs", &generate_omnixlimit_content())?;
+    create_file(&omnixtracker_path, "omnixpolicy.rs", &generate_omnixpolicy_content())?;
     create_file(&omnixtracker_path, "mod.rs", &generate_omnixtracker_mod_content())?;
     println!("Created omnixtracker directory at {:?}", omnixtracker_path);
     Ok(())
@@ -108,6 +179,8 @@ fn create_omnixtracker_files(project_path: &Path) -> Result<(), Box<dyn std::err
 fn create_utils_files(project_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
     let utils_path = project_path.join("src/utils");
     create_file(&utils_path, "lxsl.rs", &generate_lxsl_content())?;
+    create_file(&utils_path, "langreg.rs", &generate_langreg_content())?;
+    create_file(&utils_path, "xyntag.rs", &generate_xyntag_content())?;
     create_file(&utils_path, "mod.rs", &generate_utils_mod_content())?;
     println!("Created utils directory at {:?}", utils_path);
     Ok(())
@@ -118,11 +191,24 @@ fn create_tests_files(project_path: &Path, project_name: &str) -> Result<(), Box
     create_file(&tests_path, "constants_tests.rs", &generate_constants_tests_content(project_name))?;
     create_file(&tests_path, "omnixerror_tests.rs", &generate_omnixerror_tests_content(project_name))?;
     create_file(&tests_path, "omnixmetry_tests.rs", &generate_omnixmetry_tests_content(project_name))?;
+    create_file(&tests_path, "omnixlimit_tests.rs", &generate_omnixlimit_tests_content(project_name))?;
+    create_file(&tests_path, "omnixpolicy_tests.rs", &generate_omnixpolicy_tests_content(project_name))?;
     create_file(&tests_path, "utils_lxsl_tests.rs", &generate_utils_lxsl_tests_content(project_name))?;
     println!("Created tests directory at {:?}", tests_path);
     Ok(())
 }
 
+fn create_xtask_files(project_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let xtask_path = project_path.join("xtask");
+    fs::create_dir_all(xtask_path.join("src"))?;
+    fs::create_dir_all(project_path.join(".cargo"))?;
+    create_file(&xtask_path, "Cargo.toml", &generate_xtask_cargo_toml_content())?;
+    create_file(&xtask_path, "src/main.rs", &generate_xtask_main_rs_content())?;
+    create_file(project_path, ".cargo/config.toml", &generate_cargo_config_content())?;
+    println!("Created xtask directory at {:?}", xtask_path);
+    Ok(())
+}
+
 fn create_xdocs_files(project_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
     let xdocs_path = project_path.join("Xdocs");
     fs::create_dir_all(&xdocs_path)?;
@@ -140,34 +226,56 @@ fn create_xtls_files(project_path: &Path) -> Result<(), Box<dyn std::error::Erro
     Ok(())
 }
 
-fn generate_cargo_toml_content(project_name: &str) -> String {
+fn generate_cargo_toml_content(project_name: &str, header_mode: HeaderMode) -> String {
+    let workspace_members = match header_mode {
+        HeaderMode::BuildScript => "",
+        HeaderMode::Xtask => "members = [\"xtask\"]\n",
+    };
+    // Only a build.rs that stamps headers compiles lxsl and its dependencies
+    let stamping_build_dependencies = match header_mode {
+        HeaderMode::BuildScript => "rayon = \"1.10\"\nserde = { version = \"1.0\", features = [\"derive\"] }\ntoml = \"0.8\"\nwalkdir = \"2.5.0\"\n",
+        HeaderMode::Xtask => "",
+    };
+
     format!(
         r##"[package]
 name = "{}"
 version = "0.1.4"
 edition = "2021"
+rust-version = "1.89"
 authors = ["{} <{}>"]
 repository = "{}/{}"
 build = "build.rs"
 
 [build-dependencies]
 anyhow = "1.0.89"
-walkdir = "2.5.0"
-
+{}
 [dependencies]
 anyhow = "1.0.89"
-chrono = "0.4"
+chrono = {{ version = "0.4", features = ["serde"] }}
 colored = "2.0"
 dotenv = "0.15.0"
 git2 = "0.15"
 lazy_static = "1.4"
 metrics = "0.23.0"
 metrics-exporter-prometheus = "0.15"
+once_cell = "1.19"
 parking_lot = "0.12.3"
+rand = "0.8.5"
+rayon = "1.10"
+regex = "1.10"
+serde = {{ version = "1.0", features = ["derive"] }}
+serde_json = "1.0"
 thiserror = "1.0.64"
 tokio = {{ version = "1.40", features = ["full"] }}
+tokio-util = "0.7"
+toml = "0.8"
 tracing = "0.1.40"
 tracing-subscriber = {{ version = "0.3.18", features = ["env-filter", "std"] }}
+walkdir = "2.5.0"
+
+[dev-dependencies]
+tempfile = "3.12"
 
 [profile.dev]
 debug = true
@@ -184,12 +292,46 @@ lto = true
 opt-level = "z"
 
 [workspace]
-resolver = "2"
+{}resolver = "2"
 "##,
-        project_name, AUTHOR_NAME, AUTHOR_EMAIL, GITHUB_URL, project_name
+        project_name,
+        AUTHOR_NAME,
+        AUTHOR_EMAIL,
+        GITHUB_URL,
+        project_name,
+        stamping_build_dependencies,
+        workspace_members
+    )
+}
+
+fn generate_xtask_cargo_toml_content() -> String {
+    format!(
+        r#"[package]
+name = "xtask"
+version = "0.1.0"
+edition = "2021"
+authors = ["{} <{}>"]
+publish = false
+
+[dependencies]
+anyhow = "1.0.89"
+rayon = "1.10"
+serde = {{ version = "1.0", features = ["derive"] }}
+toml = "0.8"
+walkdir = "2.5.0"
+"#,
+        AUTHOR_NAME, AUTHOR_EMAIL
     )
 }
 
+fn generate_cargo_config_content() -> String {
+    r#"# .cargo/config.toml ~=#######D]======A===r===c====M===o===o===n=====<Lord[CARGO]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+[alias]
+xtask = "run --quiet --package xtask --"
+"#.to_string()
+}
+
 fn generate_prometheus_yml_content(project_name: &str) -> String {
     format!(r#"# config/prometheus.yml ~=#######D]======A===r===c====M===o===o===n=====<Lord[PROMETHEUS]Xyn>=====S===t===u===d===i===o===s======[R|$>
 
@@ -204,15 +346,19 @@ scrape_configs:
 "#, project_name)
 }
 
-fn generate_main_rs_content() -> String {
-    r##"// src/main.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[MAIN]Xyn>=====S===t===u===d===i===o===s======[R|$>
+fn generate_main_rs_content(project_name: &str) -> String {
+    render_crate_template(r##"// src/main.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[MAIN]Xyn>=====S===t===u===d===i===o===s======[R|$>
 
 use test6::constants::{CIRCUIT_BREAKER_THRESHOLD, CIRCUIT_BREAKER_DURATION, BASE_DELAY, MAX_DELAY, DEFAULT_TIMEOUT};
 use test6::omnixtracker::{OmniXMetry, setup_global_subscriber, OmniXErrorManager, OmniXErrorManagerConfig};
+use test6::omnixtracker::{ResiliencePolicies, POLICY_RELOAD_INTERVAL, RESILIENCE_CONFIG_FILE};
+use test6::utils::LordXynSignatureLine;
+use std::path::PathBuf;
+use std::sync::Arc;
 use anyhow::Result;
 use dotenv::dotenv;
 use std::env::args; 
-use tracing::info;
+use tracing::{info, warn};
 use std::env; 
 
 
@@ -245,8 +391,18 @@ async fn main() -> Result<()> {
         base_delay: *BASE_DELAY,
         max_delay: *MAX_DELAY,
         timeout: *DEFAULT_TIMEOUT,
+        ..OmniXErrorManagerConfig::default()
     };
-    let omnix_error_manager = OmniXErrorManager::new(error_manager_config.clone());
+    // Invalid policies stop startup here; later edits are picked up (or rejected) by the watcher
+    let policies = ResiliencePolicies::load(&PathBuf::from(RESILIENCE_CONFIG_FILE), &error_manager_config)?;
+    info!("Loaded resilience policies: {}", policies.names().collect::<Vec<_>>().join(", "));
+    let omnix_error_manager = Arc::new(OmniXErrorManager::new(error_manager_config.clone()).with_policies(policies));
+    let _policy_watcher = ResiliencePolicies::watch(
+        omnix_error_manager.clone(),
+        PathBuf::from(RESILIENCE_CONFIG_FILE),
+        error_manager_config.clone(),
+        POLICY_RELOAD_INTERVAL,
+    );
     info!("OmniXErrorManager initialized successfully.");
 
     // Use the omnix_error_manager to ensure it's not unused
@@ -257,7 +413,7 @@ async fn main() -> Result<()> {
 
     // Check for the minimum number of arguments
     if args.len() < 2 {
-        return Err(anyhow::anyhow!("Usage: {} --version", args[0]));
+        return Err(anyhow::anyhow!("Usage: {} --version | lxsl", args[0]));
     }
 
     // Process command-line arguments
@@ -266,6 +422,13 @@ async fn main() -> Result<()> {
             println!("xynpro version 0.1.0");
             return Ok(());
         }
+        "lxsl" => {
+            let report = LordXynSignatureLine::enforce_signatures(&env::current_dir()?)?;
+            for (path, e) in &report.errors {
+                warn!("Failed to enforce signature on {:?}: {}", path, e);
+            }
+            info!("LXSL complete ({})", report);
+        }
         _ => {
             // Future commands can be added here
             info!("No valid command provided. Exiting.");
@@ -274,7 +437,7 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
-"##.to_string()
+"##, project_name)
 }
 
 fn generate_constants_content() -> String {
@@ -285,7 +448,8 @@ use std::time::Duration;
 use tracing::Level;
 use std::env;
 
-pub const ARCMOON_SIGNATURE: &str = "~=#######D]======A===r===c====M===o===o===n=====<Lord[{}]Xyn>=====S===t===u===d===i===o===s======[R|$>";
+pub const ARCMOON_SIGNATURE: &str = crate::utils::xyntag::SIGNATURE_TEMPLATE;
+pub use crate::utils::lxsl::{LXSL_CACHE_FILE, LXSL_MAX_FILE_SIZE, LXSL_SNIFF_BYTES};
 pub const PROJECT_DIRECTORIES: &[&str] = &[
     "src/omnixtracker",
     "src/constants",
@@ -355,6 +519,13 @@ lazy_static! {
             .and_then(|v| v.parse().ok())
             .unwrap_or(30)
     );
+    // Identical errors logged again within this window are counted instead of written
+    pub static ref ERROR_LOG_WINDOW: Duration = Duration::from_secs(
+        env::var("ERROR_LOG_WINDOW")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(5)
+    );
 }
 
 pub fn get_max_retries() -> usize {
@@ -363,19 +534,20 @@ pub fn get_max_retries() -> usize {
         .and_then(|v| v.parse().ok())
         .unwrap_or(MAX_RETRIES)
 }
-"#.to_string()
-}
 
-fn generate_lib_rs_content() -> String {
-    r#"// src/lib.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[LIB]Xyn>=====S===t===u===d===i===o===s======[R|$>
-
-pub mod omnixtracker;
-pub mod constants;
-pub mod utils;
+pub fn get_rate_limit_window() -> u64 {
+    env::var("RATE_LIMIT_WINDOW")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(RATE_LIMIT_WINDOW)
+}
 
-pub use crate::omnixtracker::{OmniXMetry, OmniXErrorManager, OmniXErrorManagerConfig, setup_global_subscriber};
-pub use crate::constants::*;
-pub use crate::utils::{update_git, LordXynSignatureLine};
+pub fn get_rate_limit_max_requests() -> u32 {
+    env::var("RATE_LIMIT_MAX_REQUESTS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(RATE_LIMIT_MAX_REQUESTS)
+}
 "#.to_string()
 }
 
@@ -398,6 +570,8 @@ RATE_LIMIT_MAX_REQUESTS=100
 ENABLE_EXPERIMENTAL_FEATURES=false
 USE_LEGACY_AUTH=false
 REPO_PATH=/path/to/your/repo
+XYN_TAG_POLICY=file_stem
+ERROR_LOG_WINDOW=5
 GIT_REMOTE_URL=https://github.com/your/repo.git
 GPG_PASSPHRASE=your_gpg_passphrase_placeholder
 "#,
@@ -405,18 +579,31 @@ GPG_PASSPHRASE=your_gpg_passphrase_placeholder
     )
 }
 
-fn generate_build_rs_content() -> String {
-    r##"// build.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[BUILD]Xyn>=====S===t===u===d===i===o===s======[R|$>
-
-use std::path::{Path, Component};
-use std::fs::{File, OpenOptions};
-use std::io::{Read, Write};
-use walkdir::WalkDir;
+fn generate_build_rs_content(header_mode: HeaderMode) -> String {
+    match header_mode {
+        HeaderMode::BuildScript => {
+            let mut content = r##"// build.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[BUILD]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+#[allow(dead_code)]
+#[path = "src/utils/langreg.rs"]
+mod langreg;
+#[allow(dead_code)]
+#[path = "src/utils/xyntag.rs"]
+mod xyntag;
+#[allow(dead_code)]
+#[path = "src/utils/lxsl.rs"]
+mod lxsl;
+
+use lxsl::{LordXynSignatureLine, LxslReport};
+use std::path::Path;
+use std::fs::File;
+use std::io::Write;
 use anyhow::Result;
 use std::env;
 
 fn main() -> Result<()> {
     println!("cargo:rerun-if-env-changed=CONFIG_PATH");
+    println!("cargo:rerun-if-env-changed={}", xyntag::TAG_POLICY_ENV);
 
     let config_path = env::var("CONFIG_PATH").unwrap_or_else(|_| "config/default.toml".to_string());
 
@@ -430,138 +617,392 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+"##.to_string();
+            content.push_str(&generate_header_stamping_content());
+            content
+        }
+        HeaderMode::Xtask => r##"// build.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[BUILD]Xyn>=====S===t===u===d===i===o===s======[R|$>
 
-fn add_custom_headers(project_path: &Path) -> Result<()> {
-    for entry in WalkDir::new(project_path)
-        .into_iter()
-        .filter_entry(|e| !e.file_name().to_str().map(|s| s.starts_with('.')).unwrap_or(false))
-    {
-        let entry = entry?;
-        let path = entry.path();
-        
-        if path.is_file() {
-            let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-            if let Some(header) = generate_custom_header(project_path, path, extension) {
-                add_header_to_file(path, &header)?; // Add header to file (enforces on line 1)
-            }
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use anyhow::Result;
+use std::env;
+
+// Signature headers are stamped on demand with `cargo xtask stamp`, never during the build.
+fn main() -> Result<()> {
+    println!("cargo:rerun-if-env-changed=CONFIG_PATH");
+
+    let config_path = env::var("CONFIG_PATH").unwrap_or_else(|_| "config/default.toml".to_string());
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    let dest_path = Path::new(&out_dir).join("config.rs");
+    let mut f = File::create(&dest_path).expect("Could not create config.rs");
+    writeln!(f, "pub const CONFIG_PATH: &str = \"{}\";", config_path)
+        .expect("Could not write to config.rs");
+
+    Ok(())
+}
+"##.to_string(),
+    }
+}
+
+fn generate_xtask_main_rs_content() -> String {
+    let mut content = r##"// xtask/src/main.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[MAIN]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+#[allow(dead_code)]
+#[path = "../../src/utils/langreg.rs"]
+mod langreg;
+#[allow(dead_code)]
+#[path = "../../src/utils/xyntag.rs"]
+mod xyntag;
+#[allow(dead_code)]
+#[path = "../../src/utils/lxsl.rs"]
+mod lxsl;
+
+use lxsl::{LordXynSignatureLine, LxslReport};
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Result};
+use std::env;
+
+fn main() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("stamp") => {
+            let root = project_root();
+            let report = add_custom_headers(&root)?;
+            println!("Signature headers stamped under {:?} ({})", root, report);
+            Ok(())
         }
+        _ => bail!("Usage: cargo xtask stamp"),
     }
-    Ok(())
 }
 
-fn generate_custom_header(project_root: &Path, path: &Path, extension: &str) -> Option<String> {
-    // Ignore certain extensions
-    if extension == "toml" {
-        return None;
+fn project_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+"##.to_string();
+    content.push_str(&generate_header_stamping_content());
+    content
+}
+
+// Header stamping routines shared by the generated build.rs and xtask
+fn generate_header_stamping_content() -> String {
+    r##"
+fn add_custom_headers(project_path: &Path) -> Result<LxslReport> {
+    // Same walk as `lxsl`: parallel, and files unchanged since the last run are not reopened
+    let report = LordXynSignatureLine::enforce_signatures(project_path)?;
+    for (path, e) in &report.errors {
+        eprintln!("Failed to stamp {:?}: {}", path, e);
+    }
+    Ok(report)
+}
+"##.to_string()
+}
+
+fn generate_langreg_content() -> String {
+    let mut content = r##"// src/utils/langreg.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[LANGREG]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+// Single registry of signature comment styles.
+
+use serde::Deserialize;
+use std::sync::OnceLock;
+use std::path::Path;
+use std::{fs, io};
+
+pub const LANGUAGES_CONFIG_FILE: &str = "config/languages.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderPlacement {
+    #[default]
+    FirstLine,
+    AfterShebang,
+    AfterDeclaration,
+}
+
+impl HeaderPlacement {
+    // Index of the line the signature belongs on, given the file's current first line
+    pub fn header_line_index(&self, first_line: &str) -> usize {
+        match self {
+            HeaderPlacement::AfterShebang if first_line.starts_with("#!") => 1,
+            HeaderPlacement::AfterDeclaration if first_line.starts_with("<?") => 1,
+            _ => 0,
+        }
     }
+}
 
-    // Get the relative path
-    let relative_path = path.strip_prefix(project_root).unwrap_or(path);
-    let components: Vec<_> = relative_path.components().collect();
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LanguageSpec {
+    pub name: String,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub file_names: Vec<String>,
+    pub comment_start: String,
+    #[serde(default)]
+    pub comment_end: String,
+    #[serde(default)]
+    pub placement: HeaderPlacement,
+}
 
-    // Generate the module name
-    let module_name = if components.is_empty() {
-        "UNKNOWN".to_string()
-    } else if components.len() == 1 {
-        // Files in the root directory
-        let file_name = components[0].as_os_str().to_str().unwrap_or("UNKNOWN");
-        if file_name == "build.rs" {
-            "BUILD".to_string()
+impl LanguageSpec {
+    pub fn comment(&self, text: &str) -> String {
+        if self.comment_end.is_empty() {
+            format!("{} {}", self.comment_start, text)
         } else {
-            file_name.split('.').next().unwrap_or("UNKNOWN").to_uppercase()
+            format!("{} {} {}", self.comment_start, text, self.comment_end)
         }
-    } else if components.len() == 2 && components[0].as_os_str() == "src" {
-        // Files directly under src/
-        components[1].as_os_str().to_str().unwrap_or("UNKNOWN").split('.').next().unwrap_or("UNKNOWN").to_uppercase()
-    } else {
-        // Files in subdirectories
-        components.iter().rev().nth(1)
-            .and_then(|c| match c {
-                Component::Normal(name) => name.to_str(),
-                _ => None,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LanguagesConfig {
+    #[serde(default)]
+    languages: Vec<LanguageSpec>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LanguageRegistry {
+    languages: Vec<LanguageSpec>,
+}
+
+impl LanguageRegistry {
+    pub fn builtin() -> Self {
+        let languages = BUILTIN_LANGUAGES
+            .iter()
+            .map(|&(name, extensions, file_names, comment_start, comment_end, placement)| LanguageSpec {
+                name: name.to_string(),
+                extensions: extensions.iter().map(|s| s.to_string()).collect(),
+                file_names: file_names.iter().map(|s| s.to_string()).collect(),
+                comment_start: comment_start.to_string(),
+                comment_end: comment_end.to_string(),
+                placement,
             })
-            .unwrap_or("UNKNOWN")
-            .to_uppercase()
-    };
+            .collect();
+        Self { languages }
+    }
 
-    let module_name = module_name.replace('_', "-");
+    // Built-in languages plus the user's additions from `config_path`, if it exists
+    pub fn load(config_path: &Path) -> io::Result<Self> {
+        let mut registry = Self::builtin();
+        match fs::read_to_string(config_path) {
+            Ok(content) => {
+                let config: LanguagesConfig = toml::from_str(&content)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                registry.extend(config.languages);
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        Ok(registry)
+    }
 
-    // Determine the comment syntax based on file extension
-    let comment_syntax = match extension {
-        "rs" | "js" | "ts" | "jsx" | "tsx" | "css" | "scss" => "//",
-        "py" | "sh" | "bash" | "md" => "#",
-        "html" | "xml" => "<!--",
-        "sql" | "txt" => "--",
-        _ => return None,
-    };
+    pub fn global() -> &'static Self {
+        static REGISTRY: OnceLock<LanguageRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(|| {
+            Self::load(Path::new(LANGUAGES_CONFIG_FILE)).unwrap_or_else(|e| {
+                eprintln!("Ignoring {}: {}", LANGUAGES_CONFIG_FILE, e);
+                Self::builtin()
+            })
+        })
+    }
+
+    // User additions take precedence over built-in entries
+    pub fn extend(&mut self, languages: Vec<LanguageSpec>) {
+        let builtin = std::mem::replace(&mut self.languages, languages);
+        self.languages.extend(builtin);
+    }
 
-    let display_path = relative_path.display().to_string();
+    pub fn languages(&self) -> &[LanguageSpec] {
+        &self.languages
+    }
 
-    // Return the custom header string
-    Some(format!(
-        "{} {} ~=#######D]======A===r===c====M===o===o===n=====<Lord[{}]Xyn>=====S===t===u===d===i===o===s======[R|$>\n",
-        comment_syntax,
-        display_path,
-        module_name
-    ))
+    pub fn lookup(&self, path: &Path) -> Option<&LanguageSpec> {
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        self.languages
+            .iter()
+            .find(|l| l.file_names.iter().any(|n| n == file_name))
+            .or_else(|| self.lookup_extension(extension))
+    }
+
+    pub fn lookup_extension(&self, extension: &str) -> Option<&LanguageSpec> {
+        if extension.is_empty() {
+            return None;
+        }
+        self.languages.iter().find(|l| l.extensions.iter().any(|e| e == extension))
+    }
+
+    pub fn is_comment_line(&self, line: &str) -> bool {
+        let line = line.trim_start();
+        self.languages.iter().any(|l| line.starts_with(l.comment_start.as_str()))
+    }
 }
 
-fn add_header_to_file(path: &Path, new_header: &str) -> Result<()> {
-    let mut content = String::new();
-    {
-        let mut file = File::open(path)?;
-        file.read_to_string(&mut content)?;
+// (name, extensions, file names, comment start, comment end, placement)
+// Generated by xynpro from its language table; add project-specific languages in config/languages.toml.
+#[allow(clippy::type_complexity)]
+const BUILTIN_LANGUAGES: &[(&str, &[&str], &[&str], &str, &str, HeaderPlacement)] = &[
+"##.to_string();
+
+    for language in LANGUAGES {
+        content.push_str(&format!(
+            "    ({:?}, &{:?}, &{:?}, {:?}, {:?}, HeaderPlacement::{}),\n",
+            language.name,
+            language.extensions,
+            language.file_names,
+            language.comment_start,
+            language.comment_end,
+            language.placement
+        ));
     }
+    content.push_str("];\n");
+    content
+}
+
+fn generate_xyntag_content() -> String {
+    r##"// src/utils/xyntag.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[XYNTAG]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+// Renders signature lines for lxsl, build.rs and xtask alike.
+
+use std::path::{Component, Path};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::{env, fs};
+
+pub const SIGNATURE_TEMPLATE: &str = "~=#######D]======A===r===c====M===o===o===n=====<Lord[{}]Xyn>=====S===t===u===d===i===o===s======[R|$>";
+pub const TAG_POLICY_ENV: &str = "XYN_TAG_POLICY";
+
+// How the `<Lord[TAG]Xyn>` tag is derived from a file's path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TagPolicy {
+    // src/omnixtracker/omnixerror.rs -> OMNIXERROR
+    #[default]
+    FileStem,
+    // src/omnixtracker/omnixerror.rs -> OMNIXTRACKER
+    ParentModule,
+    // src/omnixtracker/omnixerror.rs -> OMNIXTRACKER::OMNIXERROR
+    ModulePath,
+}
 
-    let lines: Vec<&str> = content.lines().collect();
-    let mut new_lines = Vec::new();
-    let mut header_added = false;
+impl FromStr for TagPolicy {
+    type Err = String;
 
-    // Enforce header is added on line 1
-    if !lines.is_empty() {
-        if is_header(lines[0]) {
-            // If the first line already contains a valid header, we don't add a new one.
-            new_lines.push(lines[0]);
-            header_added = true;
-        } else {
-            // Add the new header to the first line
-            new_lines.push(new_header.trim_end());
-            header_added = true;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "file_stem" => Ok(TagPolicy::FileStem),
+            "parent_module" => Ok(TagPolicy::ParentModule),
+            "module_path" => Ok(TagPolicy::ModulePath),
+            other => Err(format!("Unknown tag policy: {}", other)),
         }
     }
+}
 
-    // Append the rest of the content (excluding existing signatures in the first 10 lines)
-    for (i, &line) in lines.iter().enumerate() {
-        if !is_header(line) || i >= 10 {
-            new_lines.push(line);
-        }
+impl TagPolicy {
+    // The environment wins; otherwise the project's .env, so build.rs and xtask agree with the binary
+    pub fn load(project_root: &Path) -> Self {
+        env::var(TAG_POLICY_ENV)
+            .ok()
+            .or_else(|| {
+                fs::read_to_string(project_root.join(".env"))
+                    .ok()?
+                    .lines()
+                    .find_map(|line| {
+                        line.strip_prefix(TAG_POLICY_ENV)?
+                            .strip_prefix('=')
+                            .map(|value| value.trim().trim_matches('"').to_string())
+                    })
+            })
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
     }
 
-    if !header_added {
-        // If no header has been added, insert it at the beginning of the file
-        new_lines.insert(0, new_header.trim_end());
+    pub fn global() -> Self {
+        static POLICY: OnceLock<TagPolicy> = OnceLock::new();
+        *POLICY.get_or_init(|| Self::load(Path::new(".")))
     }
+}
+
+pub fn module_tag(relative_path: &Path, policy: TagPolicy) -> String {
+    let mut segments: Vec<String> = relative_path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => name.to_str().map(str::to_string),
+            _ => None,
+        })
+        .collect();
 
-    // Write the new content to the file
-    let new_content = new_lines.join("\n");
-    let mut file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(path)?;
-    file.write_all(new_content.as_bytes())?;
+    let file_name = match segments.pop() {
+        Some(file_name) => file_name,
+        None => return "UNKNOWN".to_string(),
+    };
 
-    Ok(())
+    // A `mod.rs` is named after its directory
+    let stem = Path::new(&file_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("UNKNOWN")
+        .to_string();
+    if stem != "mod" || segments.is_empty() {
+        segments.push(stem);
+    }
+
+    // `src` directories are crate roots, not modules
+    if segments.len() > 1 {
+        segments.retain(|segment| segment != "src");
+    }
+
+    let tag_segments: Vec<&String> = match policy {
+        TagPolicy::FileStem => segments.last().into_iter().collect(),
+        TagPolicy::ParentModule => segments.iter().rev().nth(1).or(segments.last()).into_iter().collect(),
+        TagPolicy::ModulePath => segments.iter().collect(),
+    };
+
+    tag_segments
+        .iter()
+        .map(|segment| segment.to_uppercase().replace('_', "-"))
+        .collect::<Vec<_>>()
+        .join("::")
 }
 
-fn is_header(line: &str) -> bool {
-    // Check for specific header components
-    (line.contains("//src") || line.contains("// src") || line.contains("// build") || line.contains("# README") || line.contains("// tests")) &&
-    line.contains("~=#######D]======A===r===c====M===o===o===n=====<Lord[") &&
-    line.contains("]Xyn>=====S===t===u===d===i===o===s======[R|$>")
+// Path and signature, without the comment delimiters of the file's language
+pub fn signature_text(relative_path: &Path, policy: TagPolicy) -> String {
+    let display_path = relative_path.to_string_lossy().replace('\\', "/");
+    format!(
+        "{} {}",
+        display_path,
+        SIGNATURE_TEMPLATE.replace("{}", &module_tag(relative_path, policy))
+    )
 }
 "##.to_string()
 }
 
+fn generate_languages_toml_content() -> String {
+    r##"# config/languages.toml ~=#######D]======A===r===c====M===o===o===n=====<Lord[LANGUAGES]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+# Extra languages for signature stamping (lxsl, build.rs and xtask).
+# Entries here take precedence over the built-in registry in src/utils/langreg.rs.
+#
+# [[languages]]
+# name = "lua"
+# extensions = ["lua"]
+# comment_start = "--"
+#
+# [[languages]]
+# name = "nix"
+# extensions = ["nix"]
+# file_names = ["flake.lock.nix"]
+# comment_start = "#"
+# comment_end = ""
+# placement = "first_line" # first_line | after_shebang | after_declaration
+"##.to_string()
+}
+
 fn generate_lib_rs_content() -> String {
     r#"// src/lib.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[LIB]Xyn>=====S===t===u===d===i===o===s======[R|$>
 
@@ -577,9 +1018,34 @@ pub use crate::omnixtracker::{
     OmniXErrorManagerConfig,
     setup_global_subscriber,
     OmniXError,
+    ErrorCategory,
+    ErrorSeverity,
+    RetryClass,
+    OmniXContext,
+    ErrorReport,
+    ProblemDetails,
+    LogContext,
+    ErrorLogLimiter,
+    BackoffStrategy,
+    CircuitState,
+    CircuitSnapshot,
+    CircuitBreakerPolicy,
+    SlidingWindow,
+    Clock,
+    SystemClock,
+    ManualClock,
+    CircuitEvent,
+    BulkheadConfig,
+    OmniXRateLimiter,
+    RateLimitConfig,
+    RateLimitAlgorithm,
+    RateDecision,
+    CancellationToken,
+    ResiliencePolicies,
+    LabelRegistry,
 };
 pub use crate::constants::*;
-pub use crate::utils::LordXynSignatureLine;
+pub use crate::utils::{LordXynSignatureLine, LxslOutcome, LxslReport};
 
 // Note: `update_git` is not re-exported here as it's not defined in the current utils module
 "#.to_string()
@@ -686,8 +1152,6 @@ Behold, the glorious structure of your project:
 - 👶 **My Children** - for giving me the motivation to exist and persevere.
 - ❤️ **Valina** - for her love and support, and dealing with my crazy self.
 "#,
-        project_name.to_uppercase(),
-        project_name,
         project_name
     )
 }
@@ -754,17 +1218,41 @@ Thumbs.db
 }
 
 fn generate_omnixerror_content() -> String {
-    r#"// src/omnixtracker/omnixerror.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXTRACKER]Xyn>=====S===t===u===d===i===o===s======[R|$>
+    r#"// src/omnixtracker/omnixerror.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXERROR]Xyn>=====S===t===u===d===i===o===s======[R|$>
 
 use crate::omnixtracker::omnixmetry::OmniXMetry;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::omnixtracker::omnixlimit::OmniXRateLimiter;
+use crate::omnixtracker::omnixpolicy::{ResiliencePolicies, DEFAULT_POLICY};
+use crate::constants::ERROR_LOG_WINDOW;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::time::{Duration, Instant};
 use parking_lot::{Mutex, RwLock};
-use tracing::{error, info, warn};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, OnceLock};
+use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions};
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use tokio::sync::{broadcast, OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::backtrace::{Backtrace, BacktraceStatus};
+use tracing::{debug, error, info, warn};
 use git2::Error as GitError;
 use thiserror::Error;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Local, Utc};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::fmt;
 
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
 #[derive(Error, Debug)]
 pub enum OmniXError {
     #[error("Operation failed during {operation}: {details}")]
@@ -773,8 +1261,17 @@ pub enum OmniXError {
     RetryLimitExceeded { retries: usize, last_error: String },
     #[error("Circuit breaker activated after {count} errors in {duration:?}")]
     CircuitBreakerActivated { count: usize, duration: Duration },
+    // `duration` is zero when the limit is unknown, as for errors converted from tokio's `Elapsed`
     #[error("Operation timed out after {duration:?}")]
     OperationTimeout { duration: Duration },
+    #[error("Bulkhead rejected {operation}: {max_concurrent} calls in flight and {max_queued} queued")]
+    BulkheadRejected { operation: String, max_concurrent: usize, max_queued: usize },
+    #[error("Rate limit exceeded for {key}; retry after {retry_after:?}")]
+    RateLimited { key: String, retry_after: Duration },
+    #[error("Overall deadline of {deadline:?} exceeded after {attempts} attempts")]
+    DeadlineExceeded { deadline: Duration, attempts: usize },
+    #[error("Operation {operation} cancelled after {attempts} attempts")]
+    Cancelled { operation: String, attempts: usize },
     #[error("File system error: {0}")]
     FileSystemError(String),
     #[error("Environment variable error: {0}")]
@@ -795,897 +1292,4937 @@ pub enum OmniXError {
     AuthorizationError(String),
     #[error("Validation error: {0}")]
     ValidationError(String),
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+    // Wraps an error with what was being done; classification comes from `source`
+    #[error("{context}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<OmniXError>,
+        trace: Box<Backtrace>,
+    },
+    // Keeps the original foreign error; displays and classifies as `error`
+    #[error("{error}")]
+    Caused {
+        error: Box<OmniXError>,
+        #[source]
+        source: BoxError,
+        trace: Box<Backtrace>,
+    },
 }
 
-impl OmniXError {
-    pub fn log(&self) {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    Io,
+    Config,
+    Network,
+    Database,
+    Auth,
+    Validation,
+    Resilience,
+    Internal,
+}
+
+impl ErrorCategory {
+    pub fn as_str(&self) -> &'static str {
         match self {
-            OmniXError::OperationFailed { .. } | OmniXError::RetryLimitExceeded { .. } => {
-                error!("{}", self)
-            }
-            OmniXError::CircuitBreakerActivated { .. } | OmniXError::OperationTimeout { .. } => {
-                warn!("{}", self)
-            }
-            _ => {
-                info!("{}", self)
-            }
+            ErrorCategory::Io => "io",
+            ErrorCategory::Config => "config",
+            ErrorCategory::Network => "network",
+            ErrorCategory::Database => "database",
+            ErrorCategory::Auth => "auth",
+            ErrorCategory::Validation => "validation",
+            ErrorCategory::Resilience => "resilience",
+            ErrorCategory::Internal => "internal",
         }
     }
 }
 
-pub fn handle_build_error(error: Box<dyn std::error::Error>) -> OmniXError {
-    match error.downcast::<std::io::Error>() {
-        Ok(io_error) => OmniXError::FileSystemError(io_error.to_string()),
-        Err(error) => OmniXError::OperationFailed {
-            operation: "Build".to_string(),
-            details: error.to_string(),
-        },
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
-pub fn handle_main_error(error: Box<dyn std::error::Error>) -> OmniXError {
-    match error.downcast::<std::io::Error>() {
-        Ok(io_error) => OmniXError::FileSystemError(io_error.to_string()),
-        Err(error) => match error.downcast::<std::env::VarError>() {
-            Ok(var_error) => OmniXError::EnvVarError(var_error.to_string()),
-            Err(error) => OmniXError::ProjectCreationError(error.to_string()),
-        },
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorSeverity {
+    Info,
+    Warning,
+    Error,
+    Critical,
 }
 
-pub fn handle_metrics_error(error: impl std::error::Error) -> OmniXError {
-    OmniXError::MetricsInitError(error.to_string())
+impl ErrorSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorSeverity::Info => "info",
+            ErrorSeverity::Warning => "warning",
+            ErrorSeverity::Error => "error",
+            ErrorSeverity::Critical => "critical",
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct OmniXErrorManagerConfig {
-    pub max_retries: usize,
-    pub circuit_breaker_threshold: usize,
-    pub circuit_breaker_duration: Duration,
-    pub base_delay: Duration,
-    pub max_delay: Duration,
-    pub timeout: Duration,
+impl fmt::Display for ErrorSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
-impl Default for OmniXErrorManagerConfig {
-    fn default() -> Self {
-        Self {
-            max_retries: 3,
-            circuit_breaker_threshold: 10,
-            circuit_breaker_duration: Duration::from_secs(60),
-            base_delay: Duration::from_millis(100),
-            max_delay: Duration::from_secs(10),
-            timeout: Duration::from_secs(30),
-        }
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryClass {
+    Retryable,
+    Permanent,
+    // Retry, but wait at least this long before the next attempt
+    RetryAfter(Duration),
 }
 
-impl From<GitError> for OmniXError {
-    fn from(err: GitError) -> Self {
-        OmniXError::OperationFailed {
-            operation: "Git operation failed".to_string(),
-            details: err.to_string(),
+impl OmniXError {
+    // Stable machine-readable code; never renumber an existing variant
+    pub fn code(&self) -> &'static str {
+        match self {
+            OmniXError::Context { source: inner, .. } => inner.code(),
+            OmniXError::Caused { error: inner, .. } => inner.code(),
+            OmniXError::OperationFailed { .. } => "XYN-E0001",
+            OmniXError::RetryLimitExceeded { .. } => "XYN-E0002",
+            OmniXError::CircuitBreakerActivated { .. } => "XYN-E0003",
+            OmniXError::OperationTimeout { .. } => "XYN-E0004",
+            OmniXError::FileSystemError(_) => "XYN-E0005",
+            OmniXError::EnvVarError(_) => "XYN-E0006",
+            OmniXError::ProjectCreationError(_) => "XYN-E0007",
+            OmniXError::MetricsInitError(_) => "XYN-E0008",
+            OmniXError::LoggingError(_) => "XYN-E0009",
+            OmniXError::DatabaseError(_) => "XYN-E0010",
+            OmniXError::NetworkError(_) => "XYN-E0011",
+            OmniXError::AuthenticationError(_) => "XYN-E0012",
+            OmniXError::AuthorizationError(_) => "XYN-E0013",
+            OmniXError::ValidationError(_) => "XYN-E0014",
+            OmniXError::ConfigError(_) => "XYN-E0015",
+            OmniXError::BulkheadRejected { .. } => "XYN-E0016",
+            OmniXError::RateLimited { .. } => "XYN-E0017",
+            OmniXError::DeadlineExceeded { .. } => "XYN-E0018",
+            OmniXError::Cancelled { .. } => "XYN-E0019",
         }
     }
-}
 
-impl From<anyhow::Error> for OmniXError {
-    fn from(err: anyhow::Error) -> Self {
-        OmniXError::OperationFailed {
-            operation: "Unknown operation".to_string(),
-            details: err.to_string(),
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            OmniXError::Context { source: inner, .. } => inner.category(),
+            OmniXError::Caused { error: inner, .. } => inner.category(),
+            OmniXError::OperationFailed { .. } => ErrorCategory::Internal,
+            OmniXError::RetryLimitExceeded { .. }
+            | OmniXError::CircuitBreakerActivated { .. }
+            | OmniXError::OperationTimeout { .. }
+            | OmniXError::BulkheadRejected { .. }
+            | OmniXError::RateLimited { .. }
+            | OmniXError::DeadlineExceeded { .. }
+            | OmniXError::Cancelled { .. } => ErrorCategory::Resilience,
+            OmniXError::FileSystemError(_)
+            | OmniXError::ProjectCreationError(_)
+            | OmniXError::LoggingError(_) => ErrorCategory::Io,
+            OmniXError::EnvVarError(_) | OmniXError::MetricsInitError(_) | OmniXError::ConfigError(_) => {
+                ErrorCategory::Config
+            }
+            OmniXError::DatabaseError(_) => ErrorCategory::Database,
+            OmniXError::NetworkError(_) => ErrorCategory::Network,
+            OmniXError::AuthenticationError(_) | OmniXError::AuthorizationError(_) => ErrorCategory::Auth,
+            OmniXError::ValidationError(_) => ErrorCategory::Validation,
         }
     }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum CircuitState {
-    Closed,
-    Open(Instant),
-    HalfOpen,
-}
 
-impl fmt::Display for CircuitState {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    pub fn severity(&self) -> ErrorSeverity {
         match self {
-            CircuitState::Closed => write!(f, "Closed"),
-            CircuitState::Open(_) => write!(f, "Open"),
-            CircuitState::HalfOpen => write!(f, "Half-Open"),
+            OmniXError::Context { source: inner, .. } => inner.severity(),
+            OmniXError::Caused { error: inner, .. } => inner.severity(),
+            OmniXError::DatabaseError(_)
+            | OmniXError::MetricsInitError(_)
+            | OmniXError::ProjectCreationError(_) => ErrorSeverity::Critical,
+            OmniXError::OperationFailed { .. }
+            | OmniXError::RetryLimitExceeded { .. }
+            | OmniXError::FileSystemError(_)
+            | OmniXError::EnvVarError(_)
+            | OmniXError::ConfigError(_)
+            | OmniXError::LoggingError(_)
+            | OmniXError::NetworkError(_)
+            | OmniXError::AuthenticationError(_)
+            | OmniXError::AuthorizationError(_) => ErrorSeverity::Error,
+            OmniXError::CircuitBreakerActivated { .. }
+            | OmniXError::OperationTimeout { .. }
+            | OmniXError::BulkheadRejected { .. }
+            | OmniXError::RateLimited { .. }
+            | OmniXError::DeadlineExceeded { .. } => ErrorSeverity::Warning,
+            OmniXError::ValidationError(_) | OmniXError::Cancelled { .. } => ErrorSeverity::Info,
         }
     }
-}
-
-pub struct OmniXErrorManager {
-    error_count: AtomicUsize,
-    config: RwLock<OmniXErrorManagerConfig>,
-    circuit_state: Mutex<CircuitState>,
-    last_error_time: Mutex<Instant>,
-    half_open_trial_count: AtomicUsize,
-}
 
-impl OmniXErrorManager {
-    pub fn new(config: OmniXErrorManagerConfig) -> Self {
-        Self {
-            error_count: AtomicUsize::new(0),
-            config: RwLock::new(config),
-            circuit_state: Mutex::new(CircuitState::Closed),
-            last_error_time: Mutex::new(Instant::now()),
-            half_open_trial_count: AtomicUsize::new(0),
+    // Whether retrying the failed operation can help, before any config overrides
+    pub fn retry_class(&self) -> RetryClass {
+        match self {
+            OmniXError::Context { source: inner, .. } => inner.retry_class(),
+            OmniXError::Caused { error: inner, .. } => inner.retry_class(),
+            OmniXError::RateLimited { retry_after, .. } => RetryClass::RetryAfter(*retry_after),
+            OmniXError::OperationFailed { .. }
+            | OmniXError::OperationTimeout { .. }
+            | OmniXError::BulkheadRejected { .. }
+            | OmniXError::FileSystemError(_)
+            | OmniXError::LoggingError(_)
+            | OmniXError::DatabaseError(_)
+            | OmniXError::NetworkError(_) => RetryClass::Retryable,
+            OmniXError::RetryLimitExceeded { .. }
+            | OmniXError::CircuitBreakerActivated { .. }
+            | OmniXError::DeadlineExceeded { .. }
+            | OmniXError::Cancelled { .. }
+            | OmniXError::EnvVarError(_)
+            | OmniXError::ConfigError(_)
+            | OmniXError::ProjectCreationError(_)
+            | OmniXError::MetricsInitError(_)
+            | OmniXError::AuthenticationError(_)
+            | OmniXError::AuthorizationError(_)
+            | OmniXError::ValidationError(_) => RetryClass::Permanent,
         }
     }
 
-    pub async fn handle_error<T, F, Fut>(
-        &self,
-        operation: F,
-        metrics: &OmniXMetry,
-    ) -> Result<T, OmniXError>
-    where
-        F: Fn() -> Fut,
-        Fut: std::future::Future<Output = Result<T, OmniXError>>,
-    {
-        let config = self.config.read();
+    // Structured payload of the innermost variant, keyed by field name
+    pub fn fields(&self) -> BTreeMap<String, String> {
+        let pairs: Vec<(&str, String)> = match self.root() {
+            OmniXError::OperationFailed { operation, details } => {
+                vec![("operation", operation.clone()), ("details", details.clone())]
+            }
+            OmniXError::RetryLimitExceeded { retries, last_error } => {
+                vec![("retries", retries.to_string()), ("last_error", last_error.clone())]
+            }
+            OmniXError::CircuitBreakerActivated { count, duration } => {
+                vec![("count", count.to_string()), ("duration_ms", duration.as_millis().to_string())]
+            }
+            OmniXError::OperationTimeout { duration } => vec![("duration_ms", duration.as_millis().to_string())],
+            OmniXError::BulkheadRejected { operation, max_concurrent, max_queued } => vec![
+                ("operation", operation.clone()),
+                ("max_concurrent", max_concurrent.to_string()),
+                ("max_queued", max_queued.to_string()),
+            ],
+            OmniXError::RateLimited { key, retry_after } => {
+                vec![("key", key.clone()), ("retry_after_ms", retry_after.as_millis().to_string())]
+            }
+            OmniXError::DeadlineExceeded { deadline, attempts } => {
+                vec![("deadline_ms", deadline.as_millis().to_string()), ("attempts", attempts.to_string())]
+            }
+            OmniXError::Cancelled { operation, attempts } => {
+                vec![("operation", operation.clone()), ("attempts", attempts.to_string())]
+            }
+            _ => Vec::new(),
+        };
+        pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+    }
 
-        if !self.check_circuit_state() {
-            metrics.increment_counter("error_manager.circuit_opened".to_string(), 1);
-            return Err(OmniXError::CircuitBreakerActivated {
-                count: self.error_count.load(Ordering::Relaxed),
-                duration: config.circuit_breaker_duration,
-            });
+    pub fn report(&self) -> ErrorReport {
+        ErrorReport::from(self)
+    }
+
+    pub fn caused_by(error: OmniXError, source: impl Into<BoxError>) -> Self {
+        OmniXError::Caused {
+            error: Box::new(error),
+            source: source.into(),
+            trace: Box::new(Backtrace::capture()),
         }
+    }
 
-        for retries in 0..config.max_retries {
-            let start_time = Instant::now();
-            match tokio::time::timeout(config.timeout, operation()).await {
-                Ok(Ok(result)) => {
-                    self.error_count.store(0, Ordering::Relaxed);
-                    self.close_circuit();
-                    metrics.increment_counter("error_manager.successes".to_string(), 1);
-                    metrics.update_gauge("error_manager.operation_latency".to_string(), start_time.elapsed().as_secs_f64());
-                    info!("Operation succeeded on attempt {}", retries + 1);
-                    return Ok(result);
-                }
-                Ok(Err(e)) => {
-                    e.log();
-                    self.error_count.fetch_add(1, Ordering::Relaxed);
-                    *self.last_error_time.lock() = Instant::now();
+    pub fn context(self, context: impl fmt::Display) -> Self {
+        // One captured backtrace per chain is enough
+        let trace = if self.backtrace().is_some() {
+            Backtrace::disabled()
+        } else {
+            Backtrace::capture()
+        };
+        OmniXError::Context {
+            context: context.to_string(),
+            source: Box::new(self),
+            trace: Box::new(trace),
+        }
+    }
 
-                    metrics.increment_counter("error_manager.failures".to_string(), 1);
-                    metrics.increment_counter(format!("error_manager.failures.{}", e), 1);
+    // The innermost OmniXError, with context and foreign causes peeled off
+    pub fn root(&self) -> &OmniXError {
+        match self {
+            OmniXError::Context { source: inner, .. } => inner.root(),
+            OmniXError::Caused { error: inner, .. } => inner.root(),
+            _ => self,
+        }
+    }
 
-                    if self.error_count.load(Ordering::Relaxed) >= config.circuit_breaker_threshold {
-                        self.open_circuit();
-                        metrics.increment_counter("error_manager.circuit_tripped".to_string(), 1);
-                        error!(
-                            "Circuit breaker tripped after {} consecutive failures",
-                            self.error_count.load(Ordering::Relaxed)
-                        );
-                        return Err(OmniXError::CircuitBreakerActivated {
-                            count: self.error_count.load(Ordering::Relaxed),
-                            duration: config.circuit_breaker_duration,
-                        });
-                    }
+    // The dependency could not answer in time or at all, rather than rejecting the request itself, so a degraded
+    // answer is better than none; judged on the root cause, so context and foreign causes do not hide it
+    pub fn is_fallback_eligible(&self) -> bool {
+        matches!(
+            self.root(),
+            OmniXError::CircuitBreakerActivated { .. }
+                | OmniXError::RetryLimitExceeded { .. }
+                | OmniXError::DeadlineExceeded { .. }
+                | OmniXError::BulkheadRejected { .. }
+                | OmniXError::RateLimited { .. }
+                | OmniXError::OperationTimeout { .. }
+        )
+    }
 
-                    if retries == config.max_retries - 1 {
-                        metrics.increment_counter("error_manager.max_retries_exceeded".to_string(), 1);
-                        metrics.update_gauge("error_manager.operation_latency".to_string(), start_time.elapsed().as_secs_f64());
-                        return Err(OmniXError::RetryLimitExceeded {
-                            retries: retries + 1,
-                            last_error: e.to_string(),
-                        });
-                    }
+    // Backtrace captured closest to where the error originated (requires RUST_BACKTRACE or RUST_LIB_BACKTRACE)
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        let (inner, trace) = match self {
+            OmniXError::Context { source: inner, trace, .. } => (Some(inner), trace),
+            OmniXError::Caused { error: inner, trace, .. } => (Some(inner), trace),
+            _ => return None,
+        };
+        inner
+            .and_then(|inner| inner.backtrace())
+            .or_else(|| (trace.status() == BacktraceStatus::Captured).then(|| trace.as_ref()))
+    }
 
-                    let delay = config
-                        .base_delay
-                        .mul_f32(2_f32.powi(retries as i32))
-                        .min(config.max_delay);
-                    tokio::time::sleep(delay).await;
-                }
-                Err(_) => {
-                    metrics.increment_counter("error_manager.operation_timeout".to_string(), 1);
-                    metrics.update_gauge("error_manager.operation_latency".to_string(), config.timeout.as_secs_f64());
-                    return Err(OmniXError::OperationTimeout {
-                        duration: config.timeout,
-                    });
-                }
-            }
-        }
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn std::error::Error + 'static)> {
+        std::iter::successors(Some(self as &(dyn std::error::Error + 'static)), |e| e.source())
+    }
 
-        Err(OmniXError::RetryLimitExceeded {
-            retries: config.max_retries,
-            last_error: "Maximum retries reached".to_string(),
-        })
+    pub fn log(&self) {
+        self.log_with(LogContext::default())
     }
 
-    fn check_circuit_state(&self) -> bool {
-        let mut circuit_state = self.circuit_state.lock();
-        match *circuit_state {
-            CircuitState::Closed => true,
-            CircuitState::Open(opened_at) => {
-                let config = self.config.read();
-                if opened_at.elapsed() >= config.circuit_breaker_duration {
-                    *circuit_state = CircuitState::HalfOpen;
-                    self.half_open_trial_count.store(0, Ordering::Relaxed);
-                    warn!("Circuit breaker transitioning to HalfOpen state");
-                    true
-                } else {
-                    false
-                }
-            }
-            CircuitState::HalfOpen => {
-                if self.half_open_trial_count.fetch_add(1, Ordering::Relaxed) < 1 {
-                    info!("Circuit breaker is Half-Open; allowing trial operation");
-                    true
-                } else {
-                    warn!("Circuit breaker is Half-Open; trial limit reached");
-                    false
-                }
+    // Logs at the level matching `severity()`; repeats of the same error inside ERROR_LOG_WINDOW are only counted
+    pub fn log_with(&self, context: LogContext<'_>) {
+        self.log_limited(context, ErrorLogLimiter::global())
+    }
+
+    // Same as `log_with`, with repeats counted by `limiter` (and so on its clock)
+    pub fn log_limited(&self, context: LogContext<'_>, limiter: &ErrorLogLimiter) {
+        let message = self.to_string();
+        let mut hasher = DefaultHasher::new();
+        (self.code(), &message).hash(&mut hasher);
+        let suppressed = match limiter.admit(hasher.finish()) {
+            Some(suppressed) => suppressed,
+            None => return,
+        };
+
+        let root = self.root();
+        let operation = context.operation.or(match root {
+            OmniXError::OperationFailed { operation, .. }
+            | OmniXError::BulkheadRejected { operation, .. }
+            | OmniXError::Cancelled { operation, .. } => Some(operation.as_str()),
+            _ => None,
+        });
+        let retries = context.retries.or(match root {
+            OmniXError::RetryLimitExceeded { retries, .. } => Some(*retries),
+            _ => None,
+        });
+        let duration = context.duration.or(match root {
+            OmniXError::CircuitBreakerActivated { duration, .. } | OmniXError::OperationTimeout { duration } => {
+                Some(*duration)
             }
+            _ => None,
+        });
+        let caused_by = self.chain().skip(1).map(|e| e.to_string()).collect::<Vec<_>>().join(" <- ");
+
+        macro_rules! emit {
+            ($level:ident) => {
+                $level!(
+                    code = self.code(),
+                    category = self.category().as_str(),
+                    severity = self.severity().as_str(),
+                    operation,
+                    retries = retries.map(|r| r as u64),
+                    duration_ms = duration.map(|d| d.as_millis() as u64),
+                    suppressed,
+                    caused_by = %caused_by,
+                    "{}",
+                    message
+                )
+            };
+        }
+        match self.severity() {
+            ErrorSeverity::Critical | ErrorSeverity::Error => emit!(error),
+            ErrorSeverity::Warning => emit!(warn),
+            ErrorSeverity::Info => emit!(info),
+        }
+        if let Some(trace) = self.backtrace() {
+            debug!(code = self.code(), "Backtrace:\n{}", trace);
         }
     }
+}
+
+// Per-call details the error does not carry itself; `None` falls back to the error's own fields
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogContext<'a> {
+    pub operation: Option<&'a str>,
+    pub retries: Option<usize>,
+    pub duration: Option<Duration>,
+}
+
+const ERROR_LOG_LIMITER_CAPACITY: usize = 1024;
 
-    fn open_circuit(&self) {
-        let mut circuit_state = self.circuit_state.lock();
-        *circuit_state = CircuitState::Open(Instant::now());
-        self.half_open_trial_count.store(0, Ordering::Relaxed);
-        warn!("Circuit breaker opened");
+// Suppresses repeats of the same error key within `window`, counting them for the next logged occurrence
+#[derive(Debug)]
+pub struct ErrorLogLimiter {
+    window: Duration,
+    clock: Arc<dyn Clock>,
+    seen: Mutex<HashMap<u64, (Instant, u64)>>,
+}
+
+impl ErrorLogLimiter {
+    pub fn new(window: Duration) -> Self {
+        Self::with_clock(window, Arc::new(SystemClock))
     }
 
-    fn close_circuit(&self) {
-        let mut circuit_state = self.circuit_state.lock();
-        *circuit_state = CircuitState::Closed;
-        self.half_open_trial_count.store(0, Ordering::Relaxed);
-        info!("Circuit breaker closed");
+    pub fn with_clock(window: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            window,
+            clock,
+            seen: Mutex::new(HashMap::new()),
+        }
     }
 
-    pub fn update_config(&self, new_config: OmniXErrorManagerConfig) {
-        let mut config = self.config.write();
-        *config = new_config;
-        info!("OmniXErrorManager configuration updated");
+    pub fn global() -> &'static Self {
+        static LIMITER: OnceLock<ErrorLogLimiter> = OnceLock::new();
+        LIMITER.get_or_init(|| Self::new(*ERROR_LOG_WINDOW))
     }
-}
 
-impl fmt::Debug for OmniXErrorManager {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("OmniXErrorManager")
-            .field("error_count", &self.error_count.load(Ordering::Relaxed))
-            .field("config", &*self.config.read())
-            .field("circuit_state", &self.circuit_state.lock())
-            .field("last_error_time", &*self.last_error_time.lock())
-            .field("half_open_trial_count", &self.half_open_trial_count.load(Ordering::Relaxed))
-            .finish()
+    // Some(n) if this occurrence should be logged, n being the repeats swallowed since the last one
+    pub fn admit(&self, key: u64) -> Option<u64> {
+        let now = self.clock.now();
+        let mut seen = self.seen.lock();
+        if seen.len() >= ERROR_LOG_LIMITER_CAPACITY && !seen.contains_key(&key) {
+            let window = self.window;
+            seen.retain(|_, (logged_at, _)| now.duration_since(*logged_at) < window);
+            // Everything is still inside the window; forget the least recently logged key
+            if seen.len() >= ERROR_LOG_LIMITER_CAPACITY {
+                if let Some(oldest) = seen.iter().min_by_key(|(_, (logged_at, _))| *logged_at).map(|(key, _)| *key) {
+                    seen.remove(&oldest);
+                }
+            }
+        }
+        if let Some((logged_at, suppressed)) = seen.get_mut(&key) {
+            if now.duration_since(*logged_at) < self.window {
+                *suppressed += 1;
+                return None;
+            }
+            *logged_at = now;
+            return Some(std::mem::take(suppressed));
+        }
+        seen.insert(key, (now, 0));
+        Some(0)
     }
 }
-"#.to_string()
-}
 
-fn generate_omnixmetry_content() -> String {
-    r#"// src/omnixtracker/omnixmetry.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXTRACKER]Xyn>=====S===t===u===d===i===o===s======[R|$>
+// `.context()` for any result whose error converts into OmniXError
+pub trait OmniXContext<T> {
+    fn context<C: fmt::Display>(self, context: C) -> Result<T, OmniXError>;
+    fn with_context<C: fmt::Display, F: FnOnce() -> C>(self, context: F) -> Result<T, OmniXError>;
+}
 
-use crate::constants::{PROMETHEUS_LISTENER, PROMETHEUS_TEST_LISTENER, INITIAL_LOG_LEVEL, LOG_FILE_PATH};
-use tracing_subscriber::{Layer, Registry, EnvFilter};
-use metrics_exporter_prometheus::PrometheusBuilder;
-use tracing::{Event, Level, Metadata, Subscriber};
-use anyhow::{Context, Result as AnyhowResult};
-use tracing_subscriber::prelude::*;
-use std::fmt::Write as FmtWrite;
-use once_cell::sync::OnceCell;
-use std::net::TcpListener;
-use std::fs::{OpenOptions, File};
-use std::io::{Write, BufWriter};
-use parking_lot::RwLock;
-use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use chrono::Local;
-use regex::Regex;
-use colored::*;
+impl<T, E: Into<OmniXError>> OmniXContext<T> for Result<T, E> {
+    fn context<C: fmt::Display>(self, context: C) -> Result<T, OmniXError> {
+        self.map_err(|e| e.into().context(context))
+    }
 
-static PROMETHEUS_RECORDER: OnceCell<()> = OnceCell::new();
+    fn with_context<C: fmt::Display, F: FnOnce() -> C>(self, context: F) -> Result<T, OmniXError> {
+        self.map_err(|e| e.into().context(context()))
+    }
+}
 
-#[derive(Clone)]
-pub struct OmniXMetry {
-    log_file: Arc<RwLock<Option<BufWriter<File>>>>,
-    log_level: Arc<RwLock<Level>>,
+// Owned, serializable snapshot of an OmniXError for crossing process boundaries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub code: String,
+    pub category: ErrorCategory,
+    pub severity: ErrorSeverity,
+    pub message: String,
+    // Outermost first, as added with `.context()`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+    // Underlying non-OmniX errors, in source-chain order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<String>,
+    pub timestamp: DateTime<Utc>,
 }
 
-impl OmniXMetry {
-    pub fn init() -> AnyhowResult<Self> {
-        // Initialize Prometheus recorder only once
-        PROMETHEUS_RECORDER.get_or_try_init(|| {
-            let listener_result = if cfg!(test) {
-                TcpListener::bind(&*PROMETHEUS_TEST_LISTENER)
-                    .context("Failed to bind to test port")
-            } else {
-                TcpListener::bind(&*PROMETHEUS_LISTENER)
-                    .context("Failed to bind to configured Prometheus listener")
-            };
-        
-            let listener = listener_result?;
-            println!("Prometheus listening on: {}", listener.local_addr()?);
-        
-            PrometheusBuilder::new()
-                .with_http_listener(listener.local_addr()?)
-                .install_recorder()
-                .context("Failed to set global Prometheus recorder")?;
-        
-            Ok::<(), anyhow::Error>(())
-        }).context("Failed to initialize Prometheus recorder")?;
+// RFC 7807 problem details; extension members carry the report's classification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub code: String,
+    pub category: ErrorCategory,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<String>,
+    pub timestamp: DateTime<Utc>,
+}
 
-        // Open the log file
-        let log_file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&*LOG_FILE_PATH)
-            .context("Failed to open log file")?;
+pub const PROBLEM_TYPE_BASE: &str = "urn:xyn:error:";
 
-        let buffered_file = BufWriter::new(log_file);
+impl ErrorReport {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
 
-        Ok(Self {
-            log_level: Arc::new(RwLock::new(*INITIAL_LOG_LEVEL)),
-            log_file: Arc::new(RwLock::new(Some(buffered_file))),
-        })
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
     }
 
-    pub fn set_log_level(&self, level: Level) {
-        let mut log_level = self.log_level.write();
-        *log_level = level;
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
     }
 
-    pub fn get_log_level(&self) -> Level {
-        *self.log_level.read()
+    pub fn http_status(&self) -> u16 {
+        match self.category {
+            ErrorCategory::Validation => 400,
+            ErrorCategory::Auth if self.code == "XYN-E0013" => 403,
+            ErrorCategory::Auth => 401,
+            ErrorCategory::Resilience if self.code == "XYN-E0004" || self.code == "XYN-E0018" => 504,
+            ErrorCategory::Resilience if self.code == "XYN-E0017" => 429,
+            ErrorCategory::Resilience => 503,
+            ErrorCategory::Network => 502,
+            ErrorCategory::Io | ErrorCategory::Config | ErrorCategory::Database | ErrorCategory::Internal => 500,
+        }
     }
 
-    pub fn is_log_file_initialized(&self) -> bool {
-        self.log_file.read().is_some()
+    pub fn to_problem_details(&self, instance: Option<&str>) -> ProblemDetails {
+        ProblemDetails {
+            problem_type: format!("{}{}", PROBLEM_TYPE_BASE, self.code.to_lowercase()),
+            title: format!("{} error", self.category),
+            status: self.http_status(),
+            detail: self.message.clone(),
+            instance: instance.map(str::to_string),
+            code: self.code.clone(),
+            category: self.category,
+            context: self.context.clone(),
+            causes: self.causes.clone(),
+            timestamp: self.timestamp,
+        }
     }
 
-    pub fn increment_counter(&self, key_name: String, value: u64) {
-        let counter = metrics::counter!(key_name.clone(), "value" => value.to_string());
-        counter.increment(value);
+    pub fn to_problem_json(&self, instance: Option<&str>) -> serde_json::Result<String> {
+        serde_json::to_string(&self.to_problem_details(instance))
     }
-    
-    pub fn update_gauge(&self, key_name: String, value: f64) {
-        let gauge = metrics::gauge!(key_name.clone(), "value" => value.to_string());
-        gauge.set(value);
+}
+
+impl fmt::Display for ErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for context in &self.context {
+            write!(f, "{}: ", context)?;
+        }
+        write!(f, "[{}] {}", self.code, self.message)
     }
-    
-    pub fn record_histogram(&self, key_name: String, value: f64) {
-        let histogram = metrics::histogram!(key_name.clone(), "value" => value.to_string());
-        histogram.record(value);
-    }    
+}
 
-    pub fn rotate_log_file(&self) -> AnyhowResult<()> {
-        let mut log_file_lock = self.log_file.write();
-        if let Some(mut file) = log_file_lock.take() {
-            file.flush().context("Failed to flush log file before rotation")?;
-            drop(file);
-    
-            let xdocs_path = Path::new("Xdocs");
-            std::fs::create_dir_all(&xdocs_path)
-                .context("Failed to create Xdocs directory")?;
-    
-            let old_log_path = Path::new(&*LOG_FILE_PATH);
-            if old_log_path.exists() {
-                let new_log_path = self.generate_new_log_path(xdocs_path)?;
-                std::fs::rename(&old_log_path, &new_log_path)
-                    .context("Failed to rename log file")?;
-            } else {
-                println!("Warning: Log file doesn't exist. Creating a new one.");
+impl From<&OmniXError> for ErrorReport {
+    fn from(error: &OmniXError) -> Self {
+        let mut context = Vec::new();
+        let mut causes = Vec::new();
+        let mut current = error;
+        loop {
+            match current {
+                OmniXError::Context { context: text, source: inner, .. } => {
+                    context.push(text.clone());
+                    current = inner;
+                }
+                OmniXError::Caused { error: inner, source, .. } => {
+                    let foreign: &(dyn std::error::Error + 'static) = source.as_ref();
+                    causes.extend(
+                        std::iter::successors(Some(foreign), |e| e.source()).map(|e| e.to_string()),
+                    );
+                    current = inner;
+                }
+                _ => break,
             }
         }
-    
-        let new_file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&*LOG_FILE_PATH)
-            .context("Failed to open new log file")?;
-        
-        let buffered_file = BufWriter::new(new_file);
-        *log_file_lock = Some(buffered_file);
-    
-        Ok(())
+        ErrorReport {
+            code: current.code().to_string(),
+            category: current.category(),
+            severity: current.severity(),
+            message: current.to_string(),
+            context,
+            fields: current.fields(),
+            causes,
+            timestamp: Utc::now(),
+        }
     }
+}
 
-    fn generate_new_log_path(&self, xdocs_path: &Path) -> AnyhowResult<PathBuf> {
-        let date_time = Local::now().format("%m-%d-%Y_%H-%M");
-        let base_name = format!("{}", date_time);
-        
-        let regex = Regex::new(&format!(r"^{}_(\d{{3}})\.log$", regex::escape(&base_name)))
-            .context("Failed to create regex for log file naming")?;
+impl From<OmniXError> for ErrorReport {
+    fn from(error: OmniXError) -> Self {
+        ErrorReport::from(&error)
+    }
+}
 
-        let mut highest_number = 0;
-        for entry in std::fs::read_dir(xdocs_path)? {
-            let entry = entry?;
-            let file_name = entry.file_name().into_string().unwrap_or_default();
-            if let Some(captures) = regex.captures(&file_name) {
-                if let Some(number_match) = captures.get(1) {
-                    if let Ok(number) = number_match.as_str().parse::<u32>() {
-                        highest_number = highest_number.max(number);
-                    }
+// (retries so far, previous delay) -> next delay, before capping at `max_delay`
+pub type CustomBackoff = Arc<dyn Fn(usize, Duration) -> Duration + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub enum BackoffStrategy {
+    Fixed,
+    Linear,
+    #[default]
+    Exponential,
+    // Uniform in [0, exponential delay]
+    FullJitter,
+    // Uniform in [base_delay, 3 * previous delay]
+    DecorrelatedJitter,
+    Custom(CustomBackoff),
+}
+
+impl BackoffStrategy {
+    pub fn delay(
+        &self,
+        retries: usize,
+        previous: Duration,
+        base_delay: Duration,
+        max_delay: Duration,
+        rng: &mut dyn RngCore,
+    ) -> Duration {
+        let exponential = || base_delay.saturating_mul(2_u32.saturating_pow(retries.min(u32::MAX as usize) as u32));
+        let delay = match self {
+            BackoffStrategy::Fixed => base_delay,
+            BackoffStrategy::Linear => base_delay.saturating_mul((retries + 1).min(u32::MAX as usize) as u32),
+            BackoffStrategy::Exponential => exponential(),
+            BackoffStrategy::FullJitter => {
+                let ceiling = exponential().min(max_delay);
+                ceiling.mul_f64(rng.gen_range(0.0..=1.0))
+            }
+            BackoffStrategy::DecorrelatedJitter => {
+                let upper = previous.max(base_delay).saturating_mul(3);
+                if upper <= base_delay {
+                    base_delay
+                } else {
+                    rng.gen_range(base_delay..=upper)
                 }
             }
-        }
-
-        let new_number = highest_number + 1;
-        let new_file_name = format!("{}_{:03}.log", base_name, new_number);
-        Ok(xdocs_path.join(new_file_name))
+            BackoffStrategy::Custom(backoff) => backoff(retries, previous),
+        };
+        delay.min(max_delay)
     }
+}
 
-    pub fn write_log(&self, log_entry: &str) -> std::io::Result<()> {
-        if let Some(ref mut file) = *self.log_file.write() {
-            writeln!(file, "{}", log_entry)?;
-            file.flush()?;
+impl fmt::Debug for BackoffStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackoffStrategy::Fixed => write!(f, "Fixed"),
+            BackoffStrategy::Linear => write!(f, "Linear"),
+            BackoffStrategy::Exponential => write!(f, "Exponential"),
+            BackoffStrategy::FullJitter => write!(f, "FullJitter"),
+            BackoffStrategy::DecorrelatedJitter => write!(f, "DecorrelatedJitter"),
+            BackoffStrategy::Custom(_) => write!(f, "Custom(..)"),
         }
-        Ok(())
     }
 }
 
-impl<S> Layer<S> for OmniXMetry
-where
-    S: Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
-{
-    fn on_event(&self, event: &Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
-        if *event.metadata().level() <= self.get_log_level() {
-            let level_str = match *event.metadata().level() {
-                Level::ERROR => "ERROR".red(),
-                Level::WARN => "WARN ".yellow(),
-                Level::INFO => "INFO ".green(),
-                Level::DEBUG => "DEBUG".blue(),
-                Level::TRACE => "TRACE".magenta(),
-            };
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlidingWindow {
+    // The most recent N calls
+    Calls(usize),
+    // Calls made within the last duration
+    Time(Duration),
+}
 
-            let mut fields = String::new();
-            {
-                let mut visitor = FieldVisitor { output: &mut fields };
-                event.record(&mut visitor);
-            }
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CircuitBreakerPolicy {
+    // Trip after `circuit_breaker_threshold` failures in a row; any success resets the count
+    #[default]
+    ConsecutiveFailures,
+    // Trip when the failure or slow-call share of the window reaches its threshold (rates in 0.0..=1.0)
+    FailureRate {
+        window: SlidingWindow,
+        minimum_calls: usize,
+        failure_rate_threshold: f64,
+        // None disables slow-call tripping
+        slow_call_duration: Option<Duration>,
+        slow_call_rate_threshold: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkheadConfig {
+    // Attempts allowed to run at once, per operation key
+    pub max_concurrent: usize,
+    // Attempts allowed to wait for a slot; beyond this they are rejected at once
+    pub max_queued: usize,
+    pub queue_timeout: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct OmniXErrorManagerConfig {
+    pub max_retries: usize,
+    pub circuit_breaker_threshold: usize,
+    pub circuit_breaker_duration: Duration,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    // Applies to each attempt separately
+    pub timeout: Duration,
+    // Budget for the whole call, attempts and backoff sleeps included; None leaves it unbounded
+    pub overall_timeout: Option<Duration>,
+    // Treat an attempt timeout like any other retryable failure instead of returning it straight away
+    pub retry_on_timeout: bool,
+    // Per-error-code replacements for `OmniXError::retry_class`, e.g. "XYN-E0011" => Permanent
+    pub retry_overrides: HashMap<String, RetryClass>,
+    pub backoff: BackoffStrategy,
+    // Fixes the jitter sequence, e.g. for reproducible tests; None seeds from the OS
+    pub backoff_seed: Option<u64>,
+    // Upper bound on tracked operation keys; least recently used closed circuits are evicted first
+    pub max_circuits: usize,
+    pub circuit_breaker_policy: CircuitBreakerPolicy,
+    // Trial calls allowed in flight at once while half-open
+    pub half_open_max_trials: usize,
+    // Consecutive trial successes needed before the circuit closes again
+    pub half_open_success_threshold: usize,
+    // Each re-open straight from half-open multiplies the open duration, capped at `max_circuit_breaker_duration`
+    pub open_duration_multiplier: f64,
+    pub max_circuit_breaker_duration: Duration,
+    // None leaves concurrency unbounded
+    pub bulkhead: Option<BulkheadConfig>,
+    // Shares breaker state between processes, see `default_state_file`; None keeps it in memory only
+    pub state_file: Option<PathBuf>,
+}
+
+impl OmniXErrorManagerConfig {
+    pub fn retry_class_for(&self, error: &OmniXError) -> RetryClass {
+        self.retry_overrides
+            .get(error.code())
+            .copied()
+            .unwrap_or_else(|| error.retry_class())
+    }
+
+    // $XDG_STATE_HOME/<crate>/circuits.json, falling back to ~/.local/state and then Xdocs/
+    pub fn default_state_file() -> PathBuf {
+        Self::default_state_file_from(std::env::var_os("XDG_STATE_HOME"), std::env::var_os("HOME"))
+    }
+
+    // `default_state_file` with the environment passed in; empty values count as unset
+    pub fn default_state_file_from(xdg_state_home: Option<OsString>, home: Option<OsString>) -> PathBuf {
+        let state_home = xdg_state_home
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home.filter(|dir| !dir.is_empty()).map(|home| PathBuf::from(home).join(".local/state")));
+        match state_home {
+            Some(dir) => dir.join(env!("CARGO_PKG_NAME")).join("circuits.json"),
+            None => PathBuf::from("Xdocs").join("circuits.json"),
+        }
+    }
+}
+
+impl Default for OmniXErrorManagerConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            circuit_breaker_threshold: 10,
+            circuit_breaker_duration: Duration::from_secs(60),
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            timeout: Duration::from_secs(30),
+            overall_timeout: None,
+            retry_on_timeout: false,
+            retry_overrides: HashMap::new(),
+            backoff: BackoffStrategy::default(),
+            backoff_seed: None,
+            max_circuits: 256,
+            circuit_breaker_policy: CircuitBreakerPolicy::default(),
+            half_open_max_trials: 1,
+            half_open_success_threshold: 1,
+            open_duration_multiplier: 1.0,
+            max_circuit_breaker_duration: Duration::from_secs(600),
+            bulkhead: None,
+            state_file: None,
+        }
+    }
+}
+
+// `From` impls that map a foreign error onto a message-carrying variant and keep it as the source
+macro_rules! impl_from_error {
+    ($($source:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$source> for OmniXError {
+                fn from(err: $source) -> Self {
+                    OmniXError::caused_by(OmniXError::$variant(err.to_string()), err)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_error! {
+    std::env::VarError => EnvVarError,
+    std::num::ParseIntError => ValidationError,
+    std::num::ParseFloatError => ValidationError,
+    std::str::ParseBoolError => ValidationError,
+    std::str::Utf8Error => ValidationError,
+    std::string::FromUtf8Error => ValidationError,
+    std::net::AddrParseError => ConfigError,
+    chrono::ParseError => ValidationError,
+    serde_json::Error => ValidationError,
+    toml::de::Error => ConfigError,
+    toml::ser::Error => ConfigError,
+    walkdir::Error => FileSystemError,
+    metrics_exporter_prometheus::BuildError => MetricsInitError,
+    tracing::subscriber::SetGlobalDefaultError => LoggingError,
+}
+
+impl From<std::io::Error> for OmniXError {
+    fn from(err: std::io::Error) -> Self {
+        use std::io::ErrorKind;
+        let error = match err.kind() {
+            ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::AddrInUse
+            | ErrorKind::AddrNotAvailable
+            | ErrorKind::BrokenPipe
+            | ErrorKind::TimedOut => OmniXError::NetworkError(err.to_string()),
+            _ => OmniXError::FileSystemError(err.to_string()),
+        };
+        OmniXError::caused_by(error, err)
+    }
+}
+
+impl From<tokio::time::error::Elapsed> for OmniXError {
+    // Elapsed does not carry its deadline; build OperationTimeout directly where the duration is known
+    fn from(err: tokio::time::error::Elapsed) -> Self {
+        OmniXError::caused_by(OmniXError::OperationTimeout { duration: Duration::ZERO }, err)
+    }
+}
+
+impl From<tokio::task::JoinError> for OmniXError {
+    fn from(err: tokio::task::JoinError) -> Self {
+        let operation = if err.is_cancelled() { "Task cancelled" } else { "Task panicked" };
+        let error = OmniXError::OperationFailed {
+            operation: operation.to_string(),
+            details: err.to_string(),
+        };
+        OmniXError::caused_by(error, err)
+    }
+}
+
+impl From<GitError> for OmniXError {
+    fn from(err: GitError) -> Self {
+        let error = OmniXError::OperationFailed {
+            operation: "Git operation failed".to_string(),
+            details: err.to_string(),
+        };
+        OmniXError::caused_by(error, err)
+    }
+}
+
+impl From<anyhow::Error> for OmniXError {
+    fn from(err: anyhow::Error) -> Self {
+        // An OmniXError that travelled through anyhow comes back unchanged
+        match err.downcast::<OmniXError>() {
+            Ok(omnix_error) => omnix_error,
+            Err(err) => {
+                let error = OmniXError::OperationFailed {
+                    operation: "Unknown operation".to_string(),
+                    details: err.to_string(),
+                };
+                OmniXError::caused_by(error, err)
+            }
+        }
+    }
+}
+
+// Time source shared by OmniXErrorManager and OmniXMetry; swap in a ManualClock to test timing without waiting
+pub trait Clock: Send + Sync + fmt::Debug {
+    fn now(&self) -> Instant;
+    // For anything a human reads, like log timestamps and rotated log names
+    fn wall_time(&self) -> DateTime<Local>;
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn wall_time(&self) -> DateTime<Local> {
+        Local::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+// Frozen at creation time; only moves when advanced or slept on
+#[derive(Debug)]
+pub struct ManualClock {
+    start: Instant,
+    wall_start: DateTime<Local>,
+    offset: Mutex<Duration>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::at(Local::now())
+    }
+
+    pub fn at(wall_time: DateTime<Local>) -> Self {
+        Self {
+            start: Instant::now(),
+            wall_start: wall_time,
+            offset: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.offset.lock() += by;
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        *self.offset.lock()
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + *self.offset.lock()
+    }
+
+    fn wall_time(&self) -> DateTime<Local> {
+        let offset = chrono::Duration::from_std(*self.offset.lock()).unwrap_or(chrono::Duration::MAX);
+        self.wall_start.checked_add_signed(offset).unwrap_or(self.wall_start)
+    }
+
+    // Completes without waiting, with the clock moved forward as if the full duration had passed.
+    // Like tokio's paused clock it first lets anything racing it run, so a timer only fires
+    // against an operation that cannot make progress, and a timer that loses never moves the clock.
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            tokio::task::yield_now().await;
+            self.advance(duration)
+        })
+    }
+}
+
+// Circuit used by `handle_error`, which takes no operation key
+pub const DEFAULT_CIRCUIT: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open(Instant),
+    HalfOpen,
+}
+
+impl fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitState::Closed => write!(f, "Closed"),
+            CircuitState::Open(_) => write!(f, "Open"),
+            CircuitState::HalfOpen => write!(f, "Half-Open"),
+        }
+    }
+}
+
+pub const CIRCUIT_EVENT_CAPACITY: usize = 256;
+
+// Published by OmniXErrorManager after the change has happened, never while a circuit is locked
+#[derive(Debug, Clone, PartialEq)]
+pub enum CircuitEvent {
+    Opened { key: String, failures: usize, open_for: Duration },
+    HalfOpened { key: String },
+    Closed { key: String },
+    CallRejected { key: String },
+    RetryScheduled { key: String, attempt: usize, delay: Duration, code: &'static str },
+    Succeeded { key: String, attempt: usize, elapsed: Duration },
+}
+
+impl CircuitEvent {
+    pub fn key(&self) -> &str {
+        match self {
+            CircuitEvent::Opened { key, .. }
+            | CircuitEvent::HalfOpened { key }
+            | CircuitEvent::Closed { key }
+            | CircuitEvent::CallRejected { key }
+            | CircuitEvent::RetryScheduled { key, .. }
+            | CircuitEvent::Succeeded { key, .. } => key,
+        }
+    }
+}
+
+pub type CircuitEventListener = Arc<dyn Fn(&CircuitEvent) + Send + Sync>;
+
+// What a recorded outcome means for the circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Stay,
+    Trip(usize),
+    Close,
+}
+
+// How a call got past the breaker; trials carry the half-open generation that admitted them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Admission {
+    Closed,
+    Trial(u64),
+}
+
+// Which limit bounded an attempt: its own timeout, or what was left of the overall deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AttemptTimer {
+    Attempt,
+    Deadline,
+}
+
+#[derive(Debug)]
+struct Bulkhead {
+    config: BulkheadConfig,
+    permits: Arc<Semaphore>,
+    queued: AtomicUsize,
+}
+
+// Gives back a queue place even if the waiting caller is dropped
+struct QueuePlace<'a>(&'a AtomicUsize);
+
+impl Drop for QueuePlace<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Bulkhead {
+    fn new(config: BulkheadConfig) -> Self {
+        Self {
+            config,
+            permits: Arc::new(Semaphore::new(config.max_concurrent)),
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    fn in_flight(&self) -> usize {
+        self.config.max_concurrent.saturating_sub(self.permits.available_permits())
+    }
+
+    // The queue timeout runs on the manager's clock, like every other timer
+    async fn acquire(&self, clock: &dyn Clock) -> Option<OwnedSemaphorePermit> {
+        if let Ok(permit) = self.permits.clone().try_acquire_owned() {
+            return Some(permit);
+        }
+        if self.queued.fetch_add(1, Ordering::SeqCst) >= self.config.max_queued {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        let _place = QueuePlace(&self.queued);
+        tokio::select! {
+            permit = self.permits.clone().acquire_owned() => permit.ok(),
+            _ = clock.sleep(self.config.queue_timeout) => None,
+        }
+    }
+
+    fn report(&self, metrics: &OmniXMetry) {
+        metrics.update_gauge("error_manager.bulkhead_in_flight".to_string(), self.in_flight() as f64);
+        metrics.update_gauge("error_manager.bulkhead_queued".to_string(), self.queued.load(Ordering::SeqCst) as f64);
+    }
+}
+
+// Holds a bulkhead slot for one attempt and republishes the gauges once it is released
+struct BulkheadSlot<'a> {
+    bulkhead: Arc<Bulkhead>,
+    permit: Option<OwnedSemaphorePermit>,
+    metrics: &'a OmniXMetry,
+}
+
+impl Drop for BulkheadSlot<'_> {
+    fn drop(&mut self) {
+        self.permit.take();
+        self.bulkhead.report(self.metrics);
+    }
+}
+
+// Breaker bookkeeping for a single operation key
+#[derive(Debug, Clone)]
+struct Circuit {
+    state: CircuitState,
+    // Consecutive failures, whatever the policy
+    error_count: usize,
+    last_error_time: Option<Instant>,
+    half_open_in_flight: usize,
+    half_open_successes: usize,
+    half_open_generation: u64,
+    // Times the circuit went straight from half-open back to open
+    reopen_count: u32,
+    open_duration: Duration,
+    last_used: Instant,
+    // Recent outcomes for `CircuitBreakerPolicy::FailureRate`
+    calls: VecDeque<CallRecord>,
+    bulkhead: Option<Arc<Bulkhead>>,
+    // Set when something the state file records changed since it was last written
+    dirty: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CallRecord {
+    at: Instant,
+    failed: bool,
+    slow: bool,
+}
+
+// On-disk form of a circuit; instants become wall-clock times so the next process can pick them up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PersistedCircuit {
+    // Set while open; a half-open circuit is stored as open with its timer already run out
+    opened_at: Option<DateTime<Utc>>,
+    open_duration_ms: u64,
+    error_count: usize,
+    reopen_count: u32,
+}
+
+// JSON map of circuit key to `PersistedCircuit`, guarded by an advisory lock on a sibling `.lock` file so the
+// state file itself can be replaced atomically
+struct CircuitStore<'a> {
+    path: &'a Path,
+}
+
+impl<'a> CircuitStore<'a> {
+    fn new(path: &'a Path) -> Self {
+        Self { path }
+    }
+
+    fn lock(&self, exclusive: bool) -> io::Result<File> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("lock"))?;
+        if exclusive {
+            lock.lock()?;
+        } else {
+            lock.lock_shared()?;
+        }
+        // Released when the file is closed
+        Ok(lock)
+    }
+
+    fn read(&self) -> io::Result<BTreeMap<String, PersistedCircuit>> {
+        match fs::read_to_string(self.path) {
+            Ok(raw) => Ok(serde_json::from_str(&raw).unwrap_or_else(|e| {
+                warn!(path = %self.path.display(), "Ignoring unreadable circuit state: {}", e);
+                BTreeMap::new()
+            })),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn load(&self, key: &str) -> io::Result<Option<PersistedCircuit>> {
+        let _lock = self.lock(false)?;
+        Ok(self.read()?.remove(key))
+    }
+
+    // `f` reports whether it changed anything; unchanged state is not rewritten
+    fn update(&self, f: impl FnOnce(&mut BTreeMap<String, PersistedCircuit>) -> bool) -> io::Result<bool> {
+        let _lock = self.lock(true)?;
+        let mut circuits = self.read()?;
+        if !f(&mut circuits) {
+            return Ok(false);
+        }
+        let staging = self.path.with_extension("tmp");
+        fs::write(&staging, serde_json::to_vec_pretty(&circuits)?)?;
+        fs::rename(&staging, self.path)?;
+        Ok(true)
+    }
+}
+
+// Orders this process's state-file writes: a snapshot no newer than the last one written for its key is dropped
+#[derive(Debug, Default)]
+struct PersistLog {
+    // Snapshots at or below this generation predate `reset_all_circuits`
+    floor: u64,
+    written: HashMap<String, u64>,
+}
+
+impl PersistLog {
+    fn is_stale(&self, key: &str, generation: u64) -> bool {
+        generation <= self.floor || self.written.get(key).is_some_and(|&last| generation <= last)
+    }
+}
+
+impl Circuit {
+    fn new(now: Instant, config: &OmniXErrorManagerConfig) -> Self {
+        Self {
+            state: CircuitState::Closed,
+            error_count: 0,
+            last_error_time: None,
+            half_open_in_flight: 0,
+            half_open_successes: 0,
+            half_open_generation: 0,
+            reopen_count: 0,
+            open_duration: config.circuit_breaker_duration,
+            last_used: now,
+            calls: VecDeque::new(),
+            bulkhead: None,
+            dirty: false,
+        }
+    }
+
+    fn record(&mut self, failed: bool, elapsed: Duration, now: Instant, config: &OmniXErrorManagerConfig) -> Verdict {
+        self.dirty |= failed || self.error_count > 0;
+        if failed {
+            self.error_count += 1;
+            self.last_error_time = Some(now);
+        } else {
+            self.error_count = 0;
+        }
+        if let CircuitBreakerPolicy::FailureRate { window, slow_call_duration, .. } = config.circuit_breaker_policy {
+            let slow = slow_call_duration.is_some_and(|limit| elapsed >= limit);
+            self.calls.push_back(CallRecord { at: now, failed, slow });
+            self.prune(window, now);
+        }
+
+        match self.state {
+            // Stragglers admitted before the circuit opened change nothing
+            CircuitState::Open(_) => Verdict::Stay,
+            CircuitState::HalfOpen if failed => Verdict::Trip(self.failure_count(config)),
+            CircuitState::HalfOpen => {
+                self.half_open_successes += 1;
+                if self.half_open_successes >= config.half_open_success_threshold.max(1) {
+                    Verdict::Close
+                } else {
+                    Verdict::Stay
+                }
+            }
+            CircuitState::Closed => match config.circuit_breaker_policy {
+                CircuitBreakerPolicy::ConsecutiveFailures => {
+                    if failed && self.error_count >= config.circuit_breaker_threshold {
+                        Verdict::Trip(self.error_count)
+                    } else {
+                        Verdict::Stay
+                    }
+                }
+                CircuitBreakerPolicy::FailureRate {
+                    minimum_calls,
+                    failure_rate_threshold,
+                    slow_call_duration,
+                    slow_call_rate_threshold,
+                    ..
+                } => {
+                    let (calls, failures, slow_calls) = self.window_counts();
+                    if calls == 0 || calls < minimum_calls {
+                        return Verdict::Stay;
+                    }
+                    let failure_rate = failures as f64 / calls as f64;
+                    let slow_rate = slow_calls as f64 / calls as f64;
+                    if failure_rate >= failure_rate_threshold
+                        || (slow_call_duration.is_some() && slow_rate >= slow_call_rate_threshold)
+                    {
+                        Verdict::Trip(failures)
+                    } else {
+                        Verdict::Stay
+                    }
+                }
+            },
+        }
+    }
+
+    fn failure_count(&self, config: &OmniXErrorManagerConfig) -> usize {
+        match config.circuit_breaker_policy {
+            CircuitBreakerPolicy::ConsecutiveFailures => self.error_count,
+            CircuitBreakerPolicy::FailureRate { .. } => self.window_counts().1,
+        }
+    }
+
+    fn prune(&mut self, window: SlidingWindow, now: Instant) {
+        match window {
+            SlidingWindow::Calls(size) => {
+                while self.calls.len() > size {
+                    self.calls.pop_front();
+                }
+            }
+            SlidingWindow::Time(span) => {
+                while self.calls.front().is_some_and(|call| now.duration_since(call.at) > span) {
+                    self.calls.pop_front();
+                }
+            }
+        }
+    }
+
+    fn window_counts(&self) -> (usize, usize, usize) {
+        let failures = self.calls.iter().filter(|call| call.failed).count();
+        let slow_calls = self.calls.iter().filter(|call| call.slow).count();
+        (self.calls.len(), failures, slow_calls)
+    }
+
+    fn open(&mut self, now: Instant, config: &OmniXErrorManagerConfig) -> Duration {
+        self.reopen_count = match self.state {
+            CircuitState::HalfOpen => self.reopen_count.saturating_add(1),
+            _ => 0,
+        };
+        let max_duration = config.max_circuit_breaker_duration.max(config.circuit_breaker_duration);
+        let factor = config.open_duration_multiplier.max(1.0).powi(self.reopen_count.min(i32::MAX as u32) as i32);
+        self.open_duration = Duration::try_from_secs_f64(config.circuit_breaker_duration.as_secs_f64() * factor)
+            .unwrap_or(max_duration)
+            .min(max_duration);
+        self.state = CircuitState::Open(now);
+        self.half_open_in_flight = 0;
+        self.half_open_successes = 0;
+        self.dirty = true;
+        self.open_duration
+    }
+
+    fn close(&mut self, config: &OmniXErrorManagerConfig) {
+        if self.state != CircuitState::Closed {
+            // A recovered dependency starts with a clean window
+            self.calls.clear();
+        }
+        self.state = CircuitState::Closed;
+        self.error_count = 0;
+        self.half_open_in_flight = 0;
+        self.half_open_successes = 0;
+        self.reopen_count = 0;
+        self.open_duration = config.circuit_breaker_duration;
+        self.dirty = true;
+    }
+
+    // None when a fresh circuit would behave the same; the failure-rate window is not carried over
+    fn persisted(&self, now: Instant, wall_now: DateTime<Utc>) -> Option<PersistedCircuit> {
+        let since = |elapsed: Duration| wall_now - chrono::Duration::from_std(elapsed).unwrap_or_else(|_| chrono::Duration::zero());
+        let opened_at = match self.state {
+            CircuitState::Closed if self.error_count == 0 && self.reopen_count == 0 => return None,
+            CircuitState::Closed => None,
+            CircuitState::Open(opened_at) => Some(since(now.duration_since(opened_at))),
+            CircuitState::HalfOpen => Some(since(self.open_duration)),
+        };
+        Some(PersistedCircuit {
+            opened_at,
+            open_duration_ms: self.open_duration.as_millis().min(u64::MAX as u128) as u64,
+            error_count: self.error_count,
+            reopen_count: self.reopen_count,
+        })
+    }
+
+    fn restore(&mut self, persisted: &PersistedCircuit, now: Instant, wall_now: DateTime<Utc>) {
+        self.error_count = persisted.error_count;
+        self.reopen_count = persisted.reopen_count;
+        self.open_duration = Duration::from_millis(persisted.open_duration_ms);
+        if let Some(opened_at) = persisted.opened_at {
+            // A timestamp from the future (clock skew) counts as just opened
+            let elapsed = (wall_now - opened_at).to_std().unwrap_or_default();
+            self.state = match now.checked_sub(elapsed) {
+                Some(opened_at) => CircuitState::Open(opened_at),
+                // The monotonic clock does not reach back that far; shorten this open period instead
+                None => {
+                    self.open_duration = self.open_duration.saturating_sub(elapsed);
+                    CircuitState::Open(now)
+                }
+            };
+        }
+    }
+
+    fn snapshot(&self) -> CircuitSnapshot {
+        let (window_calls, window_failures, window_slow_calls) = self.window_counts();
+        CircuitSnapshot {
+            state: self.state,
+            error_count: self.error_count,
+            last_error_time: self.last_error_time,
+            window_calls,
+            window_failures,
+            window_slow_calls,
+            open_duration: self.open_duration,
+            reopen_count: self.reopen_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitSnapshot {
+    pub state: CircuitState,
+    pub error_count: usize,
+    pub last_error_time: Option<Instant>,
+    pub window_calls: usize,
+    pub window_failures: usize,
+    pub window_slow_calls: usize,
+    pub open_duration: Duration,
+    pub reopen_count: u32,
+}
+
+// Releases a half-open trial slot however the call ends
+struct TrialPermit<'a> {
+    manager: &'a OmniXErrorManager,
+    key: &'a str,
+    generation: u64,
+}
+
+impl Drop for TrialPermit<'_> {
+    fn drop(&mut self) {
+        if let Some(circuit) = self.manager.circuits.lock().get_mut(self.key) {
+            if circuit.state == CircuitState::HalfOpen && circuit.half_open_generation == self.generation {
+                circuit.half_open_in_flight = circuit.half_open_in_flight.saturating_sub(1);
+            }
+        }
+    }
+}
+
+pub struct OmniXErrorManager {
+    config: RwLock<OmniXErrorManagerConfig>,
+    circuits: Mutex<HashMap<String, Circuit>>,
+    rng: Mutex<StdRng>,
+    clock: Arc<dyn Clock>,
+    log_limiter: ErrorLogLimiter,
+    events: broadcast::Sender<CircuitEvent>,
+    listeners: RwLock<Vec<CircuitEventListener>>,
+    rate_limiter: Option<Arc<OmniXRateLimiter>>,
+    policies: RwLock<ResiliencePolicies>,
+    // Stamped on every state-file snapshot so writes finishing out of order cannot roll the file back
+    persist_generation: AtomicU64,
+    persist_log: Arc<Mutex<PersistLog>>,
+}
+
+impl OmniXErrorManager {
+    pub fn new(config: OmniXErrorManagerConfig) -> Self {
+        Self::with_clock(config, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(config: OmniXErrorManagerConfig, clock: Arc<dyn Clock>) -> Self {
+        let rng = Self::backoff_rng(config.backoff_seed);
+        let (events, _) = broadcast::channel(CIRCUIT_EVENT_CAPACITY);
+        Self {
+            policies: RwLock::new(ResiliencePolicies::from_base(&config)),
+            config: RwLock::new(config),
+            circuits: Mutex::new(HashMap::new()),
+            rng: Mutex::new(rng),
+            log_limiter: ErrorLogLimiter::with_clock(*ERROR_LOG_WINDOW, clock.clone()),
+            clock,
+            events,
+            listeners: RwLock::new(Vec::new()),
+            rate_limiter: None,
+            persist_generation: AtomicU64::new(0),
+            persist_log: Arc::new(Mutex::new(PersistLog::default())),
+        }
+    }
+
+    pub fn with_policies(self, policies: ResiliencePolicies) -> Self {
+        self.update_policies(policies);
+        self
+    }
+
+    // Swaps in a new policy set; its default policy becomes the manager config through `update_config`
+    pub fn update_policies(&self, policies: ResiliencePolicies) {
+        let default = policies.default_config().clone();
+        *self.policies.write() = policies;
+        self.update_config(default);
+    }
+
+    // DEFAULT_POLICY is always the current manager config, including changes made with `update_config`
+    pub fn policy(&self, name: &str) -> Option<OmniXErrorManagerConfig> {
+        if name == DEFAULT_POLICY {
+            return Some(self.config.read().clone());
+        }
+        self.policies.read().get(name).cloned()
+    }
+
+    pub fn policy_names(&self) -> Vec<String> {
+        self.policies.read().names().map(str::to_string).collect()
+    }
+
+    // Calls over the limit for their operation key fail with RateLimited before touching the circuit
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<OmniXRateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    // Slow receivers miss the oldest events (RecvError::Lagged) rather than holding up callers
+    pub fn subscribe(&self) -> broadcast::Receiver<CircuitEvent> {
+        self.events.subscribe()
+    }
+
+    // Runs synchronously on the calling task; keep it cheap or hand off to a channel
+    pub fn on_event(&self, listener: impl Fn(&CircuitEvent) + Send + Sync + 'static) {
+        self.listeners.write().push(Arc::new(listener));
+    }
+
+    fn emit(&self, event: CircuitEvent) {
+        let listeners = self.listeners.read().clone();
+        for listener in &listeners {
+            listener(&event);
+        }
+        // No subscribers is not an error
+        let _ = self.events.send(event);
+    }
+
+    fn backoff_rng(seed: Option<u64>) -> StdRng {
+        match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    pub async fn handle_error<T, F, Fut>(
+        &self,
+        operation: F,
+        metrics: &OmniXMetry,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        self.handle_operation(DEFAULT_CIRCUIT, operation, metrics).await
+    }
+
+    pub async fn handle_error_cancellable<T, F, Fut>(
+        &self,
+        operation: F,
+        metrics: &OmniXMetry,
+        cancel: &CancellationToken,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        self.handle_operation_cancellable(DEFAULT_CIRCUIT, operation, metrics, cancel).await
+    }
+
+    pub async fn handle_error_hedged<T, F, Fut>(
+        &self,
+        hedge_after: Duration,
+        operation: F,
+        metrics: &OmniXMetry,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        self.handle_operation_hedged(DEFAULT_CIRCUIT, hedge_after, operation, metrics).await
+    }
+
+    // Each attempt starts a second copy of `operation` if the first has not finished within `hedge_after` and takes
+    // whichever succeeds first; the pair counts as one attempt against the circuit, but the hedge needs a bulkhead slot
+    // of its own and is skipped when none is free
+    pub async fn handle_operation_hedged<T, F, Fut>(
+        &self,
+        key: &str,
+        hedge_after: Duration,
+        operation: F,
+        metrics: &OmniXMetry,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        let config = self.config.read().clone();
+        self.execute_hedged(key, hedge_after, operation, metrics, config).await
+    }
+
+    // `handle_operation_hedged` under the named policy from `config/resilience.toml`
+    pub async fn handle_operation_hedged_with_policy<T, F, Fut>(
+        &self,
+        policy: &str,
+        key: &str,
+        hedge_after: Duration,
+        operation: F,
+        metrics: &OmniXMetry,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        let config = self.resolve_policy(policy)?;
+        self.execute_hedged(key, hedge_after, operation, metrics, config).await
+    }
+
+    async fn execute_hedged<T, F, Fut>(
+        &self,
+        key: &str,
+        hedge_after: Duration,
+        operation: F,
+        metrics: &OmniXMetry,
+        config: OmniXErrorManagerConfig,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        let operation = &operation;
+        let hedged = || self.hedged_attempt(key, operation, hedge_after, metrics);
+        self.execute(key, hedged, metrics, &CancellationToken::new(), config).await
+    }
+
+    async fn hedged_attempt<T, F, Fut>(&self, key: &str, operation: &F, hedge_after: Duration, metrics: &OmniXMetry) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        let primary = operation();
+        tokio::pin!(primary);
+        tokio::select! {
+            result = &mut primary => return result,
+            _ = self.clock.sleep(hedge_after) => {}
+        }
+
+        // The primary holds the attempt's slot; the hedge must not push the key past max_concurrent
+        let bulkhead = self.circuits.lock().get(key).and_then(|circuit| circuit.bulkhead.clone());
+        let _hedge_slot = match bulkhead {
+            Some(bulkhead) => match bulkhead.permits.clone().try_acquire_owned() {
+                Ok(permit) => {
+                    bulkhead.report(metrics);
+                    Some(BulkheadSlot { bulkhead, permit: Some(permit), metrics })
+                }
+                Err(_) => {
+                    metrics.increment_counter("error_manager.hedges_skipped".to_string(), 1);
+                    return primary.await;
+                }
+            },
+            None => None,
+        };
+        metrics.increment_counter("error_manager.hedges_fired".to_string(), 1);
+        let hedge = operation();
+        tokio::pin!(hedge);
+        // A failure from one copy only counts once the other has failed too
+        let hedge_won = tokio::select! {
+            result = &mut primary => match result {
+                Ok(value) => return Ok(value),
+                Err(_) => hedge.await,
+            },
+            result = &mut hedge => match result {
+                Ok(value) => Ok(value),
+                Err(_) => return primary.await,
+            },
+        };
+        if hedge_won.is_ok() {
+            metrics.increment_counter("error_manager.hedge_wins".to_string(), 1);
+        }
+        hedge_won
+    }
+
+    pub async fn handle_error_with_fallback<T, F, Fut, FB, FutB>(
+        &self,
+        operation: F,
+        fallback: FB,
+        metrics: &OmniXMetry,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+        FB: FnOnce(OmniXError) -> FutB,
+        FutB: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        self.handle_operation_with_fallback(DEFAULT_CIRCUIT, operation, fallback, metrics).await
+    }
+
+    // `fallback` receives any error for which `is_fallback_eligible` holds; any other error is returned as is
+    pub async fn handle_operation_with_fallback<T, F, Fut, FB, FutB>(
+        &self,
+        key: &str,
+        operation: F,
+        fallback: FB,
+        metrics: &OmniXMetry,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+        FB: FnOnce(OmniXError) -> FutB,
+        FutB: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        let result = self.handle_operation(key, operation, metrics).await;
+        self.fall_back(key, result, fallback, metrics).await
+    }
+
+    // `handle_operation_with_fallback` under the named policy; an unknown policy is returned without falling back
+    pub async fn handle_operation_with_policy_and_fallback<T, F, Fut, FB, FutB>(
+        &self,
+        policy: &str,
+        key: &str,
+        operation: F,
+        fallback: FB,
+        metrics: &OmniXMetry,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+        FB: FnOnce(OmniXError) -> FutB,
+        FutB: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        let config = self.resolve_policy(policy)?;
+        let result = self.execute(key, operation, metrics, &CancellationToken::new(), config).await;
+        self.fall_back(key, result, fallback, metrics).await
+    }
+
+    async fn fall_back<T, FB, FutB>(&self, key: &str, result: Result<T, OmniXError>, fallback: FB, metrics: &OmniXMetry) -> Result<T, OmniXError>
+    where
+        FB: FnOnce(OmniXError) -> FutB,
+        FutB: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        match result {
+            Err(e) if e.is_fallback_eligible() => {
+                metrics.increment_counter("error_manager.fallback_invoked".to_string(), 1);
+                info!(circuit = key, code = e.code(), "Falling back after {}", e);
+                let result = fallback(e).await;
+                if result.is_err() {
+                    metrics.increment_counter("error_manager.fallback_failed".to_string(), 1);
+                }
+                result
+            }
+            result => result,
+        }
+    }
+
+    // Blocking twin of `handle_error` for sync callers; circuits, bulkheads and listeners are shared with async callers
+    pub fn handle_error_blocking<T, F>(&self, operation: F, metrics: &OmniXMetry) -> Result<T, OmniXError>
+    where
+        F: FnMut() -> Result<T, OmniXError>,
+    {
+        self.handle_operation_blocking(DEFAULT_CIRCUIT, operation, metrics)
+    }
+
+    // A running attempt cannot be interrupted, so `timeout` only applies to async callers; the overall deadline still
+    // cuts backoff short
+    pub fn handle_operation_blocking<T, F>(&self, key: &str, operation: F, metrics: &OmniXMetry) -> Result<T, OmniXError>
+    where
+        F: FnMut() -> Result<T, OmniXError>,
+    {
+        let operation = RefCell::new(operation);
+        self.block_on(key, self.handle_operation(key, || std::future::ready((operation.borrow_mut())()), metrics))
+    }
+
+    // `handle_operation_blocking` under the named policy from `config/resilience.toml`
+    pub fn handle_operation_blocking_with_policy<T, F>(
+        &self,
+        policy: &str,
+        key: &str,
+        operation: F,
+        metrics: &OmniXMetry,
+    ) -> Result<T, OmniXError>
+    where
+        F: FnMut() -> Result<T, OmniXError>,
+    {
+        let config = self.resolve_policy(policy)?;
+        let operation = RefCell::new(operation);
+        let cancel = CancellationToken::new();
+        self.block_on(key, self.execute(key, || std::future::ready((operation.borrow_mut())()), metrics, &cancel, config))
+    }
+
+    fn block_on<T>(&self, key: &str, run: impl Future<Output = Result<T, OmniXError>>) -> Result<T, OmniXError> {
+        match tokio::runtime::Handle::try_current() {
+            // A multi-threaded runtime moves this worker's other tasks elsewhere while it blocks
+            Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| handle.block_on(run))
+            }
+            // Blocking the only thread of a current-thread runtime would stall every task on it
+            // A programming error, so permanent: retrying it cannot help
+            Ok(_) => Err(OmniXError::ConfigError(format!(
+                "blocking call for '{}' from a current-thread runtime; use handle_operation instead",
+                key
+            ))),
+            Err(_) => Self::blocking_runtime()?.block_on(run),
+        }
+    }
+
+    // Built on first use and shared by every blocking caller; it only drives backoff sleeps and bulkhead queueing
+    fn blocking_runtime() -> Result<&'static tokio::runtime::Runtime, OmniXError> {
+        static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+        if let Some(runtime) = RUNTIME.get() {
+            return Ok(runtime);
+        }
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("omnix-blocking")
+            .enable_time()
+            .build()?;
+        Ok(RUNTIME.get_or_init(|| runtime))
+    }
+
+    // Like `handle_error`, but failures only count against the circuit named `key`
+    pub async fn handle_operation<T, F, Fut>(
+        &self,
+        key: &str,
+        operation: F,
+        metrics: &OmniXMetry,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        self.handle_operation_cancellable(key, operation, metrics, &CancellationToken::new()).await
+    }
+
+    // Cancelling `cancel` abandons the in-flight attempt or backoff sleep and ends the whole retry sequence
+    pub async fn handle_operation_cancellable<T, F, Fut>(
+        &self,
+        key: &str,
+        operation: F,
+        metrics: &OmniXMetry,
+        cancel: &CancellationToken,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        // Snapshot the config so no lock is held across awaits
+        let config = self.config.read().clone();
+        self.execute(key, operation, metrics, cancel, config).await
+    }
+
+    // Runs under the named policy from `config/resilience.toml` instead of the manager config
+    pub async fn handle_operation_with_policy<T, F, Fut>(
+        &self,
+        policy: &str,
+        key: &str,
+        operation: F,
+        metrics: &OmniXMetry,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        self.handle_operation_cancellable_with_policy(policy, key, operation, metrics, &CancellationToken::new()).await
+    }
+
+    pub async fn handle_operation_cancellable_with_policy<T, F, Fut>(
+        &self,
+        policy: &str,
+        key: &str,
+        operation: F,
+        metrics: &OmniXMetry,
+        cancel: &CancellationToken,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        let config = self.resolve_policy(policy)?;
+        self.execute(key, operation, metrics, cancel, config).await
+    }
+
+    fn resolve_policy(&self, policy: &str) -> Result<OmniXErrorManagerConfig, OmniXError> {
+        self.policy(policy)
+            .ok_or_else(|| OmniXError::ConfigError(format!("unknown resilience policy '{}'", policy)))
+    }
+
+    async fn execute<T, F, Fut>(
+        &self,
+        key: &str,
+        operation: F,
+        metrics: &OmniXMetry,
+        cancel: &CancellationToken,
+        config: OmniXErrorManagerConfig,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        let state_file = config.state_file.clone();
+        if let Some(path) = &state_file {
+            self.restore_circuit(path, key, &config).await;
+        }
+        let result = self.run_operation(key, operation, metrics, cancel, config).await;
+        if let Some(path) = &state_file {
+            self.persist_circuit(path, key).await;
+        }
+        result
+    }
+
+    async fn run_operation<T, F, Fut>(
+        &self,
+        key: &str,
+        operation: F,
+        metrics: &OmniXMetry,
+        cancel: &CancellationToken,
+        config: OmniXErrorManagerConfig,
+    ) -> Result<T, OmniXError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, OmniXError>>,
+    {
+        let deadline = config.overall_timeout.map(|budget| self.clock.now() + budget);
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(key, metrics)?;
+        }
+
+        let _trial = match self.check_circuit_state(key, &config) {
+            Some(Admission::Closed) => None,
+            Some(Admission::Trial(generation)) => Some(TrialPermit { manager: self, key, generation }),
+            None => {
+                metrics.increment_counter("error_manager.circuit_opened".to_string(), 1);
+                let (count, duration) = self.with_circuit(key, &config, |circuit| (circuit.error_count, circuit.open_duration));
+                self.emit(CircuitEvent::CallRejected { key: key.to_string() });
+                return Err(OmniXError::CircuitBreakerActivated { count, duration });
+            }
+        };
+
+        let bulkhead = self.bulkhead(key, &config);
+        let mut previous_delay = config.base_delay;
+        for retries in 0..config.max_retries {
+            let slot = match &bulkhead {
+                Some(bulkhead) => match tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => return Err(self.cancelled(key, retries, metrics)),
+                    permit = bulkhead.acquire(self.clock.as_ref()) => permit,
+                } {
+                    Some(permit) => {
+                        bulkhead.report(metrics);
+                        Some(BulkheadSlot { bulkhead: bulkhead.clone(), permit: Some(permit), metrics })
+                    }
+                    None => {
+                        metrics.increment_counter("error_manager.bulkhead_rejected".to_string(), 1);
+                        bulkhead.report(metrics);
+                        warn!(circuit = key, "Bulkhead full; rejecting call");
+                        return Err(OmniXError::BulkheadRejected {
+                            operation: key.to_string(),
+                            max_concurrent: bulkhead.config.max_concurrent,
+                            max_queued: bulkhead.config.max_queued,
+                        });
+                    }
+                },
+                None => None,
+            };
+            let start_time = self.clock.now();
+            let (attempt_timeout, timer) = match deadline.map(|deadline| deadline.saturating_duration_since(start_time)) {
+                Some(remaining) if remaining < config.timeout => (remaining, AttemptTimer::Deadline),
+                _ => (config.timeout, AttemptTimer::Attempt),
+            };
+            // Timed on the manager's clock, like backoff, so a ManualClock governs both
+            let outcome = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => return Err(self.cancelled(key, retries, metrics)),
+                outcome = operation() => Some(outcome),
+                _ = self.clock.sleep(attempt_timeout) => None,
+            };
+            // Backoff must not hold a slot while the dependency sits idle
+            drop(slot);
+            let timed_out = outcome.is_none();
+            let outcome = match outcome {
+                Some(outcome) => outcome,
+                None => {
+                    metrics.increment_counter("error_manager.operation_timeout".to_string(), 1);
+                    metrics.update_gauge("error_manager.operation_latency".to_string(), attempt_timeout.as_secs_f64());
+                    if timer == AttemptTimer::Deadline {
+                        // A dependency that hangs until the deadline is as unhealthy as one that times out each attempt
+                        let now = self.clock.now();
+                        let verdict = self.with_circuit(key, &config, |circuit| circuit.record(true, attempt_timeout, now, &config));
+                        if let Verdict::Trip(count) = verdict {
+                            self.open_circuit(key, count, &config);
+                            metrics.increment_counter("error_manager.circuit_tripped".to_string(), 1);
+                            error!(circuit = key, "Circuit breaker tripped after {} failures", count);
+                        }
+                        return Err(self.deadline_exceeded(key, &config, retries + 1, metrics));
+                    }
+                    // Goes through the failure path below, so it counts against the circuit either way
+                    Err(OmniXError::OperationTimeout { duration: attempt_timeout })
+                }
+            };
+            match outcome {
+                Ok(result) => {
+                    let now = self.clock.now();
+                    let elapsed = now.duration_since(start_time);
+                    let verdict = self.with_circuit(key, &config, |circuit| circuit.record(false, elapsed, now, &config));
+                    metrics.increment_counter("error_manager.successes".to_string(), 1);
+                    metrics.update_gauge("error_manager.operation_latency".to_string(), elapsed.as_secs_f64());
+                    info!(circuit = key, "Operation succeeded on attempt {}", retries + 1);
+                    self.emit(CircuitEvent::Succeeded { key: key.to_string(), attempt: retries + 1, elapsed });
+                    match verdict {
+                        // Slow-call tripping; the call itself still succeeded
+                        Verdict::Trip(failures) => {
+                            self.open_circuit(key, failures, &config);
+                            metrics.increment_counter("error_manager.circuit_tripped".to_string(), 1);
+                            warn!(circuit = key, failures, "Circuit breaker tripped on slow-call rate");
+                        }
+                        Verdict::Close => self.close_circuit(key, &config),
+                        Verdict::Stay => {}
+                    }
+                    return Ok(result);
+                }
+                Err(e) => {
+                    let now = self.clock.now();
+                    let elapsed = now.duration_since(start_time);
+                    e.log_limited(LogContext {
+                        operation: Some(key),
+                        retries: Some(retries),
+                        duration: Some(elapsed),
+                    }, &self.log_limiter);
+
+                    let retry_class = config.retry_class_for(&e);
+                    if retry_class == RetryClass::Permanent {
+                        // Caller-side failures say nothing about the dependency's health
+                        metrics.increment_counter("error_manager.permanent_failures".to_string(), 1);
+                        return Err(e);
+                    }
+
+                    let verdict = self.with_circuit(key, &config, |circuit| circuit.record(true, elapsed, now, &config));
+
+                    metrics.increment_counter("error_manager.failures".to_string(), 1);
+                    metrics
+                        .counter(
+                            "error_manager.failures_by_code",
+                            &[("code", e.code()), ("category", e.category().as_str()), ("severity", e.severity().as_str())],
+                        )
+                        .increment(1);
+
+                    if let Verdict::Trip(count) = verdict {
+                        let duration = self.open_circuit(key, count, &config);
+                        metrics.increment_counter("error_manager.circuit_tripped".to_string(), 1);
+                        error!(circuit = key, "Circuit breaker tripped after {} failures", count);
+                        return Err(OmniXError::CircuitBreakerActivated { count, duration });
+                    }
+
+                    if timed_out && !config.retry_on_timeout {
+                        return Err(e);
+                    }
+
+                    if retries == config.max_retries - 1 {
+                        metrics.increment_counter("error_manager.max_retries_exceeded".to_string(), 1);
+                        metrics.update_gauge("error_manager.operation_latency".to_string(), elapsed.as_secs_f64());
+                        return Err(OmniXError::RetryLimitExceeded {
+                            retries: retries + 1,
+                            last_error: e.to_string(),
+                        });
+                    }
+
+                    let delay = match retry_class {
+                        // The hint never outlasts max_delay or the time left before the deadline
+                        RetryClass::RetryAfter(hint) => {
+                            let capped = hint.min(config.max_delay);
+                            deadline.map_or(capped, |deadline| capped.min(deadline.saturating_duration_since(now)))
+                        }
+                        _ => config.backoff.delay(
+                            retries,
+                            previous_delay,
+                            config.base_delay,
+                            config.max_delay,
+                            &mut *self.rng.lock(),
+                        ),
+                    };
+                    previous_delay = delay;
+                    // Sleeping past the deadline only to give up afterwards helps nobody
+                    if deadline.is_some_and(|deadline| now + delay >= deadline) {
+                        return Err(self.deadline_exceeded(key, &config, retries + 1, metrics));
+                    }
+                    metrics.record_histogram("error_manager.retry_delay_ms".to_string(), delay.as_secs_f64() * 1000.0);
+                    self.emit(CircuitEvent::RetryScheduled {
+                        key: key.to_string(),
+                        attempt: retries + 2,
+                        delay,
+                        code: e.code(),
+                    });
+                    tokio::select! {
+                        biased;
+                        _ = cancel.cancelled() => return Err(self.cancelled(key, retries + 1, metrics)),
+                        _ = self.clock.sleep(delay) => {}
+                    }
+                }
+            }
+        }
+
+        Err(OmniXError::RetryLimitExceeded {
+            retries: config.max_retries,
+            last_error: "Maximum retries reached".to_string(),
+        })
+    }
+
+    fn deadline_exceeded(&self, key: &str, config: &OmniXErrorManagerConfig, attempts: usize, metrics: &OmniXMetry) -> OmniXError {
+        metrics.increment_counter("error_manager.deadline_exceeded".to_string(), 1);
+        warn!(circuit = key, attempts, "Overall deadline exceeded");
+        OmniXError::DeadlineExceeded {
+            deadline: config.overall_timeout.unwrap_or(config.timeout),
+            attempts,
+        }
+    }
+
+    fn cancelled(&self, key: &str, attempts: usize, metrics: &OmniXMetry) -> OmniXError {
+        metrics.increment_counter("error_manager.cancelled".to_string(), 1);
+        info!(circuit = key, attempts, "Operation cancelled");
+        OmniXError::Cancelled { operation: key.to_string(), attempts }
+    }
+
+    // Runs `f` on the circuit for `key`, creating it (and evicting if full) on first use
+    fn with_circuit<R>(&self, key: &str, config: &OmniXErrorManagerConfig, f: impl FnOnce(&mut Circuit) -> R) -> R {
+        let now = self.clock.now();
+        let mut circuits = self.circuits.lock();
+        if !circuits.contains_key(key) {
+            if circuits.len() >= config.max_circuits.max(1) {
+                Self::evict_circuit(&mut circuits);
+            }
+            circuits.insert(key.to_string(), Circuit::new(now, config));
+        }
+        let circuit = circuits.get_mut(key).expect("circuit was just inserted");
+        circuit.last_used = now;
+        f(circuit)
+    }
+
+    // Callers still holding slots from a replaced bulkhead keep them until they finish
+    fn bulkhead(&self, key: &str, config: &OmniXErrorManagerConfig) -> Option<Arc<Bulkhead>> {
+        let bulkhead_config = config.bulkhead?;
+        Some(self.with_circuit(key, config, |circuit| match &circuit.bulkhead {
+            Some(bulkhead) if bulkhead.config == bulkhead_config => bulkhead.clone(),
+            _ => circuit.bulkhead.insert(Arc::new(Bulkhead::new(bulkhead_config))).clone(),
+        }))
+    }
+
+    // Drops the least recently used circuit, preferring closed ones so open breakers are not forgotten
+    fn evict_circuit(circuits: &mut HashMap<String, Circuit>) {
+        let victim = circuits
+            .iter()
+            .min_by_key(|(_, circuit)| (circuit.state != CircuitState::Closed, circuit.last_used))
+            .map(|(key, _)| key.clone());
+        if let Some(key) = victim {
+            circuits.remove(&key);
+            debug!(circuit = %key, "Evicted circuit breaker");
+        }
+    }
+
+    fn check_circuit_state(&self, key: &str, config: &OmniXErrorManagerConfig) -> Option<Admission> {
+        let now = self.clock.now();
+        let mut half_opened = false;
+        let admission = self.with_circuit(key, config, |circuit| match circuit.state {
+            CircuitState::Closed => Some(Admission::Closed),
+            CircuitState::Open(opened_at) => {
+                if now.duration_since(opened_at) >= circuit.open_duration {
+                    circuit.state = CircuitState::HalfOpen;
+                    circuit.half_open_generation += 1;
+                    circuit.half_open_in_flight = 1;
+                    circuit.half_open_successes = 0;
+                    half_opened = true;
+                    Some(Admission::Trial(circuit.half_open_generation))
+                } else {
+                    None
+                }
+            }
+            CircuitState::HalfOpen => {
+                if circuit.half_open_in_flight < config.half_open_max_trials.max(1) {
+                    circuit.half_open_in_flight += 1;
+                    info!(circuit = key, "Circuit breaker is Half-Open; allowing trial operation");
+                    Some(Admission::Trial(circuit.half_open_generation))
+                } else {
+                    warn!(circuit = key, "Circuit breaker is Half-Open; trial limit reached");
+                    None
+                }
+            }
+        });
+        if half_opened {
+            warn!(circuit = key, "Circuit breaker transitioning to HalfOpen state");
+            self.emit(CircuitEvent::HalfOpened { key: key.to_string() });
+        }
+        admission
+    }
+
+    fn open_circuit(&self, key: &str, failures: usize, config: &OmniXErrorManagerConfig) -> Duration {
+        let now = self.clock.now();
+        let duration = self.with_circuit(key, config, |circuit| circuit.open(now, config));
+        warn!(circuit = key, open_for = ?duration, "Circuit breaker opened");
+        self.emit(CircuitEvent::Opened { key: key.to_string(), failures, open_for: duration });
+        duration
+    }
+
+    fn close_circuit(&self, key: &str, config: &OmniXErrorManagerConfig) {
+        self.with_circuit(key, config, |circuit| circuit.close(config));
+        info!(circuit = key, "Circuit breaker closed");
+        self.emit(CircuitEvent::Closed { key: key.to_string() });
+    }
+
+    pub fn circuit(&self, key: &str) -> Option<CircuitSnapshot> {
+        self.circuits.lock().get(key).map(Circuit::snapshot)
+    }
+
+    pub fn circuit_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.circuits.lock().keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    // Forgets everything about `key`; the next call starts from a fresh closed circuit
+    pub fn reset_circuit(&self, key: &str) -> bool {
+        let removed = self.circuits.lock().remove(key);
+        if let Some(circuit) = &removed {
+            info!(circuit = key, "Circuit breaker reset");
+            if circuit.state != CircuitState::Closed {
+                self.emit(CircuitEvent::Closed { key: key.to_string() });
+            }
+        }
+        let mut forgotten = false;
+        if let Some(path) = self.config.read().state_file.clone() {
+            // Holding the log keeps snapshots taken before the reset from landing after it
+            let mut log = self.persist_log.lock();
+            let generation = self.persist_generation.fetch_add(1, Ordering::SeqCst) + 1;
+            match CircuitStore::new(&path).update(|circuits| circuits.remove(key).is_some()) {
+                Ok(changed) => forgotten = changed,
+                Err(e) => warn!(circuit = key, path = %path.display(), "Failed to clear circuit state: {}", e),
+            }
+            log.written.insert(key.to_string(), generation);
+        }
+        removed.is_some() || forgotten
+    }
+
+    pub fn reset_all_circuits(&self) {
+        self.circuits.lock().clear();
+        info!("All circuit breakers reset");
+        if let Some(path) = self.config.read().state_file.clone() {
+            let mut log = self.persist_log.lock();
+            if let Err(e) = CircuitStore::new(&path).update(|circuits| !std::mem::take(circuits).is_empty()) {
+                warn!(path = %path.display(), "Failed to clear circuit state: {}", e);
+            }
+            log.floor = self.persist_generation.fetch_add(1, Ordering::SeqCst) + 1;
+            log.written.clear();
+        }
+    }
+
+    // Seeds a circuit this process has not used yet from the state file; another process may have opened it
+    async fn restore_circuit(&self, path: &Path, key: &str, config: &OmniXErrorManagerConfig) {
+        if self.circuits.lock().contains_key(key) {
+            return;
+        }
+        let (store_path, store_key) = (path.to_path_buf(), key.to_string());
+        let loaded = tokio::task::spawn_blocking(move || CircuitStore::new(&store_path).load(&store_key)).await;
+        let persisted = match loaded {
+            Ok(Ok(Some(persisted))) => persisted,
+            Ok(Ok(None)) => return,
+            Ok(Err(e)) => return warn!(circuit = key, path = %path.display(), "Failed to load circuit state: {}", e),
+            Err(e) => return warn!(circuit = key, "Circuit state load task failed: {}", e),
+        };
+        let now = self.clock.now();
+        let wall_now = self.clock.wall_time().with_timezone(&Utc);
+        let mut circuits = self.circuits.lock();
+        // A concurrent call got here first and its circuit is already live
+        if circuits.contains_key(key) {
+            return;
+        }
+        if circuits.len() >= config.max_circuits.max(1) {
+            Self::evict_circuit(&mut circuits);
+        }
+        let mut circuit = Circuit::new(now, config);
+        circuit.restore(&persisted, now, wall_now);
+        circuits.insert(key.to_string(), circuit);
+    }
+
+    // Writes the circuit for `key` through to the state file after a transition or failure-count change, or drops its
+    // entry once there is nothing worth keeping; calls that leave the recorded state alone skip the file entirely
+    async fn persist_circuit(&self, path: &Path, key: &str) {
+        let now = self.clock.now();
+        let wall_now = self.clock.wall_time().with_timezone(&Utc);
+        let (persisted, generation) = {
+            let mut circuits = self.circuits.lock();
+            let Some(circuit) = circuits.get_mut(key).filter(|circuit| circuit.dirty) else {
+                return;
+            };
+            circuit.dirty = false;
+            // Taken under the circuits lock so generations follow the order the snapshots were taken in
+            (circuit.persisted(now, wall_now), self.persist_generation.fetch_add(1, Ordering::SeqCst) + 1)
+        };
+        let log = self.persist_log.clone();
+        let (store_path, store_key) = (path.to_path_buf(), key.to_string());
+        let written = tokio::task::spawn_blocking(move || {
+            let mut log = log.lock();
+            if log.is_stale(&store_key, generation) {
+                return Ok(());
+            }
+            CircuitStore::new(&store_path).update(|circuits| match persisted {
+                Some(persisted) => circuits.insert(store_key.clone(), persisted.clone()).as_ref() != Some(&persisted),
+                None => circuits.remove(&store_key).is_some(),
+            })?;
+            log.written.insert(store_key, generation);
+            Ok::<_, io::Error>(())
+        })
+        .await;
+        match written {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!(circuit = key, path = %path.display(), "Failed to persist circuit state: {}", e),
+            Err(e) => warn!(circuit = key, "Circuit state write task failed: {}", e),
+        }
+    }
+
+    pub fn update_config(&self, new_config: OmniXErrorManagerConfig) {
+        let mut config = self.config.write();
+        if new_config.backoff_seed.is_some() && new_config.backoff_seed != config.backoff_seed {
+            *self.rng.lock() = Self::backoff_rng(new_config.backoff_seed);
+        }
+        *config = new_config;
+        info!("OmniXErrorManager configuration updated");
+    }
+}
+
+impl fmt::Debug for OmniXErrorManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let circuits: BTreeMap<String, CircuitSnapshot> = self
+            .circuits
+            .lock()
+            .iter()
+            .map(|(key, circuit)| (key.clone(), circuit.snapshot()))
+            .collect();
+        f.debug_struct("OmniXErrorManager")
+            .field("config", &*self.config.read())
+            .field("circuits", &circuits)
+            .finish()
+    }
+}
+"#.to_string()
+}
+
+fn generate_omnixmetry_content() -> String {
+    r#"// src/omnixtracker/omnixmetry.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXMETRY]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+use crate::constants::{PROMETHEUS_LISTENER, PROMETHEUS_TEST_LISTENER, INITIAL_LOG_LEVEL, LOG_FILE_PATH};
+use crate::omnixtracker::omnixerror::{Clock, SystemClock};
+use tracing_subscriber::{Layer, Registry, EnvFilter};
+use metrics_exporter_prometheus::PrometheusBuilder;
+use tracing::{warn, Event, Level, Metadata, Subscriber};
+use anyhow::{Context, Result as AnyhowResult};
+use std::collections::{HashMap, HashSet};
+use tracing_subscriber::prelude::*;
+use std::fmt::Write as FmtWrite;
+use once_cell::sync::OnceCell;
+use std::net::TcpListener;
+use std::fs::{OpenOptions, File};
+use std::io::{Write, BufWriter};
+use parking_lot::{Mutex, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use regex::Regex;
+use colored::*;
+
+static PROMETHEUS_RECORDER: OnceCell<()> = OnceCell::new();
+
+// Stands in for label values past a key's cardinality limit
+pub const OVERFLOW_LABEL_VALUE: &str = "__other__";
+pub const DEFAULT_LABEL_CARDINALITY: usize = 64;
+// Label keys the crate itself emits
+const DEFAULT_LABEL_KEYS: &[&str] = &["code", "category", "severity"];
+
+#[derive(Debug, Default)]
+struct LabelSeries {
+    values: HashSet<String>,
+    warned: bool,
+}
+
+// Allowed label keys and how many distinct values each may take per metric; anything else is dropped (unknown keys)
+// or bucketed into OVERFLOW_LABEL_VALUE (excess values), with one warning per metric and key
+#[derive(Debug, Default)]
+pub struct LabelRegistry {
+    allowed: RwLock<HashMap<String, usize>>,
+    series: Mutex<HashMap<(String, String), LabelSeries>>,
+}
+
+impl LabelRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_defaults() -> Self {
+        let registry = Self::new();
+        for key in DEFAULT_LABEL_KEYS {
+            registry.allow(key, DEFAULT_LABEL_CARDINALITY);
+        }
+        registry
+    }
+
+    pub fn global() -> Arc<Self> {
+        static REGISTRY: OnceLock<Arc<LabelRegistry>> = OnceLock::new();
+        REGISTRY.get_or_init(|| Arc::new(Self::with_defaults())).clone()
+    }
+
+    pub fn allow(&self, key: &str, max_values: usize) {
+        self.allowed.write().insert(key.to_string(), max_values);
+    }
+
+    pub fn is_allowed(&self, key: &str) -> bool {
+        self.allowed.read().contains_key(key)
+    }
+
+    // The labels `metric` is actually recorded with
+    pub fn resolve(&self, metric: &str, labels: &[(&str, &str)]) -> Vec<(String, String)> {
+        let allowed = self.allowed.read();
+        let mut series = self.series.lock();
+        let mut resolved = Vec::with_capacity(labels.len());
+        for &(key, value) in labels {
+            let state = series.entry((metric.to_string(), key.to_string())).or_default();
+            let Some(&max_values) = allowed.get(key) else {
+                if !std::mem::replace(&mut state.warned, true) {
+                    warn!(metric, label = key, "Dropping unregistered metric label");
+                }
+                metrics::counter!("omnixmetry.labels_dropped").increment(1);
+                continue;
+            };
+            if state.values.contains(value) || state.values.len() < max_values {
+                state.values.insert(value.to_string());
+                resolved.push((key.to_string(), value.to_string()));
+            } else {
+                if !std::mem::replace(&mut state.warned, true) {
+                    warn!(metric, label = key, max_values, "Label cardinality limit reached; bucketing further values");
+                }
+                metrics::counter!("omnixmetry.label_values_bucketed").increment(1);
+                resolved.push((key.to_string(), OVERFLOW_LABEL_VALUE.to_string()));
+            }
+        }
+        resolved
+    }
+}
+
+#[derive(Clone)]
+pub struct OmniXMetry {
+    log_file: Arc<RwLock<Option<BufWriter<File>>>>,
+    log_level: Arc<RwLock<Level>>,
+    clock: Arc<dyn Clock>,
+    labels: Arc<LabelRegistry>,
+}
+
+impl OmniXMetry {
+    pub fn init() -> AnyhowResult<Self> {
+        // Initialize Prometheus recorder only once
+        PROMETHEUS_RECORDER.get_or_try_init(|| {
+            let listener_result = if cfg!(test) {
+                TcpListener::bind(&*PROMETHEUS_TEST_LISTENER)
+                    .context("Failed to bind to test port")
+            } else {
+                TcpListener::bind(&*PROMETHEUS_LISTENER)
+                    .context("Failed to bind to configured Prometheus listener")
+            };
+        
+            let listener = listener_result?;
+            println!("Prometheus listening on: {}", listener.local_addr()?);
+        
+            PrometheusBuilder::new()
+                .with_http_listener(listener.local_addr()?)
+                .install_recorder()
+                .context("Failed to set global Prometheus recorder")?;
+        
+            Ok::<(), anyhow::Error>(())
+        }).context("Failed to initialize Prometheus recorder")?;
+
+        // Open the log file
+        let log_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&*LOG_FILE_PATH)
+            .context("Failed to open log file")?;
+
+        let buffered_file = BufWriter::new(log_file);
+
+        Ok(Self {
+            log_level: Arc::new(RwLock::new(*INITIAL_LOG_LEVEL)),
+            log_file: Arc::new(RwLock::new(Some(buffered_file))),
+            clock: Arc::new(SystemClock),
+            labels: LabelRegistry::global(),
+        })
+    }
+
+    // No Prometheus exporter and no log file; metrics go to whatever recorder is installed, if any
+    pub fn detached() -> Self {
+        Self {
+            log_level: Arc::new(RwLock::new(*INITIAL_LOG_LEVEL)),
+            log_file: Arc::new(RwLock::new(None)),
+            clock: Arc::new(SystemClock),
+            labels: LabelRegistry::global(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_label_registry(mut self, labels: Arc<LabelRegistry>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn label_registry(&self) -> &Arc<LabelRegistry> {
+        &self.labels
+    }
+
+    pub fn set_log_level(&self, level: Level) {
+        let mut log_level = self.log_level.write();
+        *log_level = level;
+    }
+
+    pub fn get_log_level(&self) -> Level {
+        *self.log_level.read()
+    }
+
+    pub fn is_log_file_initialized(&self) -> bool {
+        self.log_file.read().is_some()
+    }
+
+    // Labels go through the label registry; keep values bounded (codes, states), never messages or ids
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)]) -> metrics::Counter {
+        metrics::counter!(name.to_string(), self.metric_labels(name, labels))
+    }
+
+    pub fn gauge(&self, name: &str, labels: &[(&str, &str)]) -> metrics::Gauge {
+        metrics::gauge!(name.to_string(), self.metric_labels(name, labels))
+    }
+
+    pub fn histogram(&self, name: &str, labels: &[(&str, &str)]) -> metrics::Histogram {
+        metrics::histogram!(name.to_string(), self.metric_labels(name, labels))
+    }
+
+    fn metric_labels(&self, name: &str, labels: &[(&str, &str)]) -> Vec<metrics::Label> {
+        self.labels
+            .resolve(name, labels)
+            .into_iter()
+            .map(|(key, value)| metrics::Label::new(key, value))
+            .collect()
+    }
+
+    pub fn increment_counter(&self, key_name: String, value: u64) {
+        metrics::counter!(key_name).increment(value);
+    }
+
+    // Shorthand for `counter(..).increment(..)`, so labels are resolved by the label registry like any other metric
+    pub fn increment_labeled_counter(&self, key_name: String, value: u64, labels: &[(&str, &str)]) {
+        self.counter(&key_name, labels).increment(value);
+    }
+
+    pub fn update_gauge(&self, key_name: String, value: f64) {
+        metrics::gauge!(key_name).set(value);
+    }
+
+    pub fn record_histogram(&self, key_name: String, value: f64) {
+        metrics::histogram!(key_name).record(value);
+    }
+
+    pub fn rotate_log_file(&self) -> AnyhowResult<()> {
+        let mut log_file_lock = self.log_file.write();
+        if let Some(mut file) = log_file_lock.take() {
+            file.flush().context("Failed to flush log file before rotation")?;
+            drop(file);
+    
+            let xdocs_path = Path::new("Xdocs");
+            std::fs::create_dir_all(&xdocs_path)
+                .context("Failed to create Xdocs directory")?;
+    
+            let old_log_path = Path::new(&*LOG_FILE_PATH);
+            if old_log_path.exists() {
+                let new_log_path = self.generate_new_log_path(xdocs_path)?;
+                std::fs::rename(&old_log_path, &new_log_path)
+                    .context("Failed to rename log file")?;
+            } else {
+                println!("Warning: Log file doesn't exist. Creating a new one.");
+            }
+        }
+    
+        let new_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&*LOG_FILE_PATH)
+            .context("Failed to open new log file")?;
+        
+        let buffered_file = BufWriter::new(new_file);
+        *log_file_lock = Some(buffered_file);
+    
+        Ok(())
+    }
+
+    pub fn generate_new_log_path(&self, xdocs_path: &Path) -> AnyhowResult<PathBuf> {
+        let date_time = self.clock.wall_time().format("%m-%d-%Y_%H-%M");
+        let base_name = format!("{}", date_time);
+        
+        let regex = Regex::new(&format!(r"^{}_(\d{{3}})\.log$", regex::escape(&base_name)))
+            .context("Failed to create regex for log file naming")?;
+
+        let mut highest_number = 0;
+        for entry in std::fs::read_dir(xdocs_path)? {
+            let entry = entry?;
+            let file_name = entry.file_name().into_string().unwrap_or_default();
+            if let Some(captures) = regex.captures(&file_name) {
+                if let Some(number_match) = captures.get(1) {
+                    if let Ok(number) = number_match.as_str().parse::<u32>() {
+                        highest_number = highest_number.max(number);
+                    }
+                }
+            }
+        }
+
+        let new_number = highest_number + 1;
+        let new_file_name = format!("{}_{:03}.log", base_name, new_number);
+        Ok(xdocs_path.join(new_file_name))
+    }
+
+    pub fn write_log(&self, log_entry: &str) -> std::io::Result<()> {
+        if let Some(ref mut file) = *self.log_file.write() {
+            writeln!(file, "{}", log_entry)?;
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<S> Layer<S> for OmniXMetry
+where
+    S: Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+        if *event.metadata().level() <= self.get_log_level() {
+            let level_str = match *event.metadata().level() {
+                Level::ERROR => "ERROR".red(),
+                Level::WARN => "WARN ".yellow(),
+                Level::INFO => "INFO ".green(),
+                Level::DEBUG => "DEBUG".blue(),
+                Level::TRACE => "TRACE".magenta(),
+            };
+
+            let mut fields = String::new();
+            {
+                let mut visitor = FieldVisitor { output: &mut fields };
+                event.record(&mut visitor);
+            }
+
+            let log_entry = format!(
+                "{} [{}] {}: {}",
+                self.clock.wall_time().format("%B, %d %Y @ %I:%M %p"),
+                level_str,
+                event.metadata().target(),
+                fields
+            );
+
+            println!("{}", log_entry);
+
+            if let Err(e) = self.write_log(&log_entry) {
+                eprintln!("Failed to write to log file: {}", e);
+            }
+        }
+    }
+
+    fn enabled(
+        &self,
+        metadata: &Metadata<'_>,
+        _: tracing_subscriber::layer::Context<'_, S>,
+    ) -> bool {
+        *metadata.level() <= self.get_log_level()
+    }
+}
+
+struct FieldVisitor<'a> {
+    output: &'a mut String,
+}
+
+impl<'a> tracing::field::Visit for FieldVisitor<'a> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if !self.output.is_empty() {
+            self.output.push_str(", ");
+        }
+        let _ = write!(self.output, "{} = {:?}", field.name(), value);
+    }
+}
+
+pub fn setup_global_subscriber(omnixmetry: OmniXMetry) -> AnyhowResult<()> {
+    let env_filter = EnvFilter::try_from_default_env().or_else(|_| EnvFilter::try_new("info"))?;
+    let subscriber = Registry::default().with(env_filter).with(omnixmetry);
+    tracing::subscriber::set_global_default(subscriber).context("Failed to set global subscriber")?;
+    Ok(())
+}
+"#.to_string()
+}
+
+fn generate_omnixlimit_content() -> String {
+    r#"// src/omnixtracker/omnixlimit.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXLIMIT]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+use crate::constants::{get_rate_limit_max_requests, get_rate_limit_window};
+use crate::omnixtracker::omnixerror::{Clock, OmniXError, SystemClock};
+use crate::omnixtracker::omnixmetry::OmniXMetry;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+use tracing::debug;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitAlgorithm {
+    // Bursts up to `max_requests`, refilled evenly over `window`
+    #[default]
+    TokenBucket,
+    // At most `max_requests` in any trailing `window`; exact, but keeps one timestamp per request
+    SlidingWindowLog,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    pub algorithm: RateLimitAlgorithm,
+    pub window: Duration,
+    pub max_requests: u32,
+    // Upper bound on tracked caller keys; idle keys are dropped first
+    pub max_keys: usize,
+}
+
+impl RateLimitConfig {
+    // RATE_LIMIT_WINDOW and RATE_LIMIT_MAX_REQUESTS from the environment, falling back to the constants
+    pub fn from_env() -> Self {
+        Self {
+            window: Duration::from_secs(get_rate_limit_window()),
+            max_requests: get_rate_limit_max_requests(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: RateLimitAlgorithm::default(),
+            window: Duration::from_secs(crate::constants::RATE_LIMIT_WINDOW),
+            max_requests: crate::constants::RATE_LIMIT_MAX_REQUESTS,
+            max_keys: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateDecision {
+    Allowed { remaining: u32 },
+    Denied { retry_after: Duration },
+}
+
+#[derive(Debug)]
+enum KeyState {
+    Bucket { tokens: f64, refilled_at: Instant },
+    Log(VecDeque<Instant>),
+}
+
+#[derive(Debug)]
+struct KeyEntry {
+    state: KeyState,
+    last_seen: Instant,
+}
+
+pub struct OmniXRateLimiter {
+    config: RateLimitConfig,
+    clock: Arc<dyn Clock>,
+    keys: Mutex<HashMap<String, KeyEntry>>,
+}
+
+impl OmniXRateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self::with_clock(config, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(config: RateLimitConfig, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            config,
+            clock,
+            keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> RateLimitConfig {
+        self.config
+    }
+
+    // Takes one request from `key`'s allowance if there is any left
+    pub fn check(&self, key: &str) -> RateDecision {
+        let now = self.clock.now();
+        let mut keys = self.keys.lock();
+        if !keys.contains_key(key) && keys.len() >= self.config.max_keys.max(1) {
+            self.evict(&mut keys, now);
+        }
+        let entry = keys.entry(key.to_string()).or_insert_with(|| KeyEntry {
+            state: self.initial_state(now),
+            last_seen: now,
+        });
+        entry.last_seen = now;
+
+        let max_requests = self.config.max_requests;
+        let window = self.config.window;
+        match &mut entry.state {
+            KeyState::Bucket { tokens, refilled_at } => {
+                let rate = max_requests as f64 / window.as_secs_f64().max(f64::EPSILON);
+                *tokens = (*tokens + now.duration_since(*refilled_at).as_secs_f64() * rate).min(max_requests as f64);
+                *refilled_at = now;
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+                    RateDecision::Allowed { remaining: *tokens as u32 }
+                } else if max_requests == 0 {
+                    RateDecision::Denied { retry_after: window }
+                } else {
+                    RateDecision::Denied { retry_after: Duration::from_secs_f64((1.0 - *tokens) / rate) }
+                }
+            }
+            KeyState::Log(requests) => {
+                while requests.front().is_some_and(|at| now.duration_since(*at) >= window) {
+                    requests.pop_front();
+                }
+                if requests.len() < max_requests as usize {
+                    requests.push_back(now);
+                    RateDecision::Allowed { remaining: max_requests - requests.len() as u32 }
+                } else {
+                    let oldest = requests.front().copied().unwrap_or(now);
+                    RateDecision::Denied { retry_after: window.saturating_sub(now.duration_since(oldest)) }
+                }
+            }
+        }
+    }
+
+    // `check` with metrics, turning a denial into OmniXError::RateLimited
+    pub fn acquire(&self, key: &str, metrics: &OmniXMetry) -> Result<u32, OmniXError> {
+        match self.check(key) {
+            RateDecision::Allowed { remaining } => {
+                metrics.increment_counter("rate_limiter.allowed".to_string(), 1);
+                Ok(remaining)
+            }
+            RateDecision::Denied { retry_after } => {
+                metrics.increment_counter("rate_limiter.denied".to_string(), 1);
+                Err(OmniXError::RateLimited { key: key.to_string(), retry_after })
+            }
+        }
+    }
+
+    pub fn reset(&self, key: &str) -> bool {
+        self.keys.lock().remove(key).is_some()
+    }
+
+    pub fn tracked_keys(&self) -> usize {
+        self.keys.lock().len()
+    }
+
+    fn initial_state(&self, now: Instant) -> KeyState {
+        match self.config.algorithm {
+            RateLimitAlgorithm::TokenBucket => KeyState::Bucket {
+                tokens: self.config.max_requests as f64,
+                refilled_at: now,
+            },
+            RateLimitAlgorithm::SlidingWindowLog => KeyState::Log(VecDeque::new()),
+        }
+    }
+
+    // Keys idle for a whole window have a full allowance again, so forgetting them changes nothing
+    fn evict(&self, keys: &mut HashMap<String, KeyEntry>, now: Instant) {
+        let window = self.config.window;
+        keys.retain(|_, entry| now.duration_since(entry.last_seen) < window);
+        if keys.len() >= self.config.max_keys.max(1) {
+            let oldest = keys.iter().min_by_key(|(_, entry)| entry.last_seen).map(|(key, _)| key.clone());
+            if let Some(key) = oldest {
+                keys.remove(&key);
+                debug!(key = %key, "Evicted rate limiter key");
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for OmniXRateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OmniXRateLimiter")
+            .field("config", &self.config)
+            .field("tracked_keys", &self.tracked_keys())
+            .finish()
+    }
+}
+"#.to_string()
+}
+
+fn generate_omnixpolicy_content() -> String {
+    r#"// src/omnixtracker/omnixpolicy.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXPOLICY]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+use crate::omnixtracker::omnixerror::{
+    BackoffStrategy, BulkheadConfig, CircuitBreakerPolicy, OmniXContext, OmniXError, OmniXErrorManager,
+    OmniXErrorManagerConfig, SlidingWindow,
+};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::Deserialize;
+use tracing::{info, warn};
+use std::sync::Arc;
+use std::{fs, io};
+
+pub const RESILIENCE_CONFIG_FILE: &str = "config/resilience.toml";
+// Poll interval main.rs hands to `ResiliencePolicies::watch`, so the longest an edit to the policy file takes to apply
+pub const POLICY_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+// Drives calls that do not name a policy; falls back to the base config when the file leaves it out
+pub const DEFAULT_POLICY: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum BackoffKind {
+    Fixed,
+    Linear,
+    Exponential,
+    FullJitter,
+    DecorrelatedJitter,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BreakerSpec {
+    threshold: Option<usize>,
+    duration_secs: Option<u64>,
+    max_duration_secs: Option<u64>,
+    open_duration_multiplier: Option<f64>,
+    half_open_max_trials: Option<usize>,
+    half_open_success_threshold: Option<usize>,
+    // Setting this switches the policy from consecutive failures to a failure rate over `window_calls`; the keys
+    // below only apply under it
+    failure_rate: Option<f64>,
+    // The window is either the last `window_calls` calls or the last `window_secs` seconds, never both
+    window_calls: Option<usize>,
+    window_secs: Option<u64>,
+    minimum_calls: Option<usize>,
+    // Calls at least this slow count towards `slow_call_rate`
+    slow_call_ms: Option<u64>,
+    slow_call_rate: Option<f64>,
+}
+
+impl BreakerSpec {
+    // The window settings would otherwise be silently ignored under the consecutive-failure policy
+    fn check(&self) -> Result<(), String> {
+        if self.window_calls.is_some() && self.window_secs.is_some() {
+            return Err("breaker window_calls and window_secs are mutually exclusive".to_string());
+        }
+        if self.failure_rate.is_some() {
+            return Ok(());
+        }
+        let rate_only = [
+            ("window_calls", self.window_calls.is_some()),
+            ("window_secs", self.window_secs.is_some()),
+            ("minimum_calls", self.minimum_calls.is_some()),
+            ("slow_call_ms", self.slow_call_ms.is_some()),
+            ("slow_call_rate", self.slow_call_rate.is_some()),
+        ];
+        match rate_only.iter().find(|(_, set)| *set) {
+            Some((key, _)) => Err(format!("breaker {} needs failure_rate to be set", key)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BulkheadSpec {
+    max_concurrent: usize,
+    #[serde(default)]
+    max_queued: usize,
+    #[serde(default)]
+    queue_timeout_ms: u64,
+}
+
+// One `[policies.<name>]` table; anything left out keeps the base config's value. `retry_overrides` and
+// `state_file` are not policy settings: they come from the base config for every policy
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicySpec {
+    max_retries: Option<usize>,
+    backoff: Option<BackoffKind>,
+    base_delay_ms: Option<u64>,
+    max_delay_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    overall_timeout_ms: Option<u64>,
+    retry_on_timeout: Option<bool>,
+    breaker: Option<BreakerSpec>,
+    bulkhead: Option<BulkheadSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PoliciesFile {
+    #[serde(default)]
+    policies: BTreeMap<String, PolicySpec>,
+}
+
+impl PolicySpec {
+    fn check(&self) -> Result<(), String> {
+        self.breaker.as_ref().map_or(Ok(()), BreakerSpec::check)
+    }
+
+    fn apply(self, base: &OmniXErrorManagerConfig) -> OmniXErrorManagerConfig {
+        let mut config = base.clone();
+        let millis = Duration::from_millis;
+        if let Some(max_retries) = self.max_retries {
+            config.max_retries = max_retries;
+        }
+        if let Some(backoff) = self.backoff {
+            config.backoff = match backoff {
+                BackoffKind::Fixed => BackoffStrategy::Fixed,
+                BackoffKind::Linear => BackoffStrategy::Linear,
+                BackoffKind::Exponential => BackoffStrategy::Exponential,
+                BackoffKind::FullJitter => BackoffStrategy::FullJitter,
+                BackoffKind::DecorrelatedJitter => BackoffStrategy::DecorrelatedJitter,
+            };
+        }
+        config.base_delay = self.base_delay_ms.map_or(config.base_delay, millis);
+        config.max_delay = self.max_delay_ms.map_or(config.max_delay, millis);
+        config.timeout = self.timeout_ms.map_or(config.timeout, millis);
+        config.overall_timeout = self.overall_timeout_ms.map(millis).or(config.overall_timeout);
+        config.retry_on_timeout = self.retry_on_timeout.unwrap_or(config.retry_on_timeout);
+
+        if let Some(breaker) = self.breaker {
+            config.circuit_breaker_threshold = breaker.threshold.unwrap_or(config.circuit_breaker_threshold);
+            config.circuit_breaker_duration = breaker.duration_secs.map_or(config.circuit_breaker_duration, Duration::from_secs);
+            config.max_circuit_breaker_duration =
+                breaker.max_duration_secs.map_or(config.max_circuit_breaker_duration, Duration::from_secs);
+            config.open_duration_multiplier = breaker.open_duration_multiplier.unwrap_or(config.open_duration_multiplier);
+            config.half_open_max_trials = breaker.half_open_max_trials.unwrap_or(config.half_open_max_trials);
+            config.half_open_success_threshold =
+                breaker.half_open_success_threshold.unwrap_or(config.half_open_success_threshold);
+            if let Some(failure_rate_threshold) = breaker.failure_rate {
+                config.circuit_breaker_policy = CircuitBreakerPolicy::FailureRate {
+                    window: match breaker.window_secs {
+                        Some(secs) => SlidingWindow::Time(Duration::from_secs(secs)),
+                        None => SlidingWindow::Calls(breaker.window_calls.unwrap_or(100)),
+                    },
+                    minimum_calls: breaker.minimum_calls.unwrap_or(10),
+                    failure_rate_threshold,
+                    slow_call_duration: breaker.slow_call_ms.map(millis),
+                    slow_call_rate_threshold: breaker.slow_call_rate.unwrap_or(1.0),
+                };
+            }
+        }
+        if let Some(bulkhead) = self.bulkhead {
+            config.bulkhead = Some(BulkheadConfig {
+                max_concurrent: bulkhead.max_concurrent,
+                max_queued: bulkhead.max_queued,
+                queue_timeout: millis(bulkhead.queue_timeout_ms),
+            });
+        }
+        config
+    }
+}
+
+// Catches settings that would make the manager misbehave rather than fail loudly
+pub fn validate_config(config: &OmniXErrorManagerConfig) -> Result<(), OmniXError> {
+    check_config(config).map_err(OmniXError::ConfigError)
+}
+
+fn check_config(config: &OmniXErrorManagerConfig) -> Result<(), String> {
+    if config.max_retries == 0 {
+        return Err("max_retries must be at least 1".to_string());
+    }
+    if config.base_delay > config.max_delay {
+        return Err(format!("base delay {:?} exceeds max delay {:?}", config.base_delay, config.max_delay));
+    }
+    if config.timeout.is_zero() {
+        return Err("timeout must be greater than zero".to_string());
+    }
+    if config.overall_timeout.is_some_and(|overall| overall.is_zero()) {
+        return Err("overall timeout must be greater than zero".to_string());
+    }
+    if config.circuit_breaker_threshold == 0 {
+        return Err("breaker threshold must be at least 1".to_string());
+    }
+    if !(config.open_duration_multiplier >= 1.0 && config.open_duration_multiplier.is_finite()) {
+        return Err(format!("open duration multiplier {} must be a finite value of at least 1.0", config.open_duration_multiplier));
+    }
+    if let CircuitBreakerPolicy::FailureRate { failure_rate_threshold, slow_call_rate_threshold, window, .. } =
+        config.circuit_breaker_policy
+    {
+        if !(failure_rate_threshold > 0.0 && failure_rate_threshold <= 1.0) {
+            return Err(format!("failure rate {} must be within (0.0, 1.0]", failure_rate_threshold));
+        }
+        if !(slow_call_rate_threshold > 0.0 && slow_call_rate_threshold <= 1.0) {
+            return Err(format!("slow call rate {} must be within (0.0, 1.0]", slow_call_rate_threshold));
+        }
+        if window == SlidingWindow::Calls(0) {
+            return Err("failure rate window must hold at least one call".to_string());
+        }
+        if window == SlidingWindow::Time(Duration::ZERO) {
+            return Err("failure rate window must span some time".to_string());
+        }
+    }
+    if config.bulkhead.is_some_and(|bulkhead| bulkhead.max_concurrent == 0) {
+        return Err("bulkhead max_concurrent must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+// Named manager configs, each built by applying a `[policies.<name>]` table over a shared base
+#[derive(Debug, Clone)]
+pub struct ResiliencePolicies {
+    policies: BTreeMap<String, OmniXErrorManagerConfig>,
+}
+
+impl ResiliencePolicies {
+    // Just the base config, under DEFAULT_POLICY
+    pub fn from_base(base: &OmniXErrorManagerConfig) -> Self {
+        let mut policies = BTreeMap::new();
+        policies.insert(DEFAULT_POLICY.to_string(), base.clone());
+        Self { policies }
+    }
+
+    pub fn from_toml(content: &str, base: &OmniXErrorManagerConfig) -> Result<Self, OmniXError> {
+        validate_config(base).context("base resilience config")?;
+        let file: PoliciesFile = toml::from_str(content)?;
+        let mut policies = Self::from_base(base);
+        for (name, spec) in file.policies {
+            let context = || format!("resilience policy '{}'", name);
+            spec.check().map_err(OmniXError::ConfigError).with_context(context)?;
+            let config = spec.apply(base);
+            validate_config(&config).with_context(context)?;
+            policies.policies.insert(name, config);
+        }
+        Ok(policies)
+    }
+
+    // A missing file is not an error; every call then runs on the base config
+    pub fn load(config_path: &Path, base: &OmniXErrorManagerConfig) -> Result<Self, OmniXError> {
+        let content = match fs::read_to_string(config_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Self::from_toml(&content, base).with_context(|| config_path.display().to_string())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&OmniXErrorManagerConfig> {
+        self.policies.get(name)
+    }
+
+    pub fn default_config(&self) -> &OmniXErrorManagerConfig {
+        &self.policies[DEFAULT_POLICY]
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.policies.keys().map(String::as_str)
+    }
+
+    // Re-reads `config_path` every `interval` and hands valid changes to the manager; a broken edit is logged and the
+    // previous policies stay in force
+    pub fn watch(
+        manager: Arc<OmniXErrorManager>,
+        config_path: PathBuf,
+        base: OmniXErrorManagerConfig,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        // Taken before spawning so edits made right after this call are not mistaken for the baseline
+        let mut last_seen = fs::read_to_string(&config_path).ok();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let current = fs::read_to_string(&config_path).ok();
+                if current == last_seen {
+                    continue;
+                }
+                match Self::load(&config_path, &base) {
+                    Ok(policies) => {
+                        info!(path = %config_path.display(), "Reloaded resilience policies");
+                        manager.update_policies(policies);
+                    }
+                    Err(e) => warn!(path = %config_path.display(), "Keeping previous resilience policies: {}", e),
+                }
+                last_seen = current;
+            }
+        })
+    }
+}
+"#.to_string()
+}
+
+fn generate_resilience_toml_content() -> String {
+    r#"# config/resilience.toml ~=#######D]======A===r===c====M===o===o===n=====<Lord[RESILIENCE]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+# Named resilience policies for OmniXErrorManager, validated at startup and reloaded on change.
+# Edits are picked up within POLICY_RELOAD_INTERVAL (5s); an invalid edit is logged and ignored.
+# Each table overrides the env-derived base config; omitted keys keep the base value.
+# A policy named "default" applies to calls that do not select one.
+# retry_overrides and state_file are not configurable here; every policy uses the base config's.
+#
+# backoff = "fixed" | "linear" | "exponential" | "full_jitter" | "decorrelated_jitter"
+# breaker: window_calls or window_secs (not both), minimum_calls, slow_call_ms and slow_call_rate require failure_rate
+
+[policies.interactive]
+max_retries = 2
+backoff = "full_jitter"
+base_delay_ms = 50
+max_delay_ms = 500
+timeout_ms = 2000
+overall_timeout_ms = 5000
+retry_on_timeout = true
+
+[policies.interactive.breaker]
+threshold = 5
+duration_secs = 30
+
+[policies.background]
+max_retries = 5
+backoff = "decorrelated_jitter"
+base_delay_ms = 500
+max_delay_ms = 30000
+timeout_ms = 60000
+
+[policies.background.breaker]
+failure_rate = 0.5
+window_calls = 50
+minimum_calls = 10
+open_duration_multiplier = 2.0
+max_duration_secs = 600
+
+[policies.background.bulkhead]
+max_concurrent = 4
+max_queued = 16
+queue_timeout_ms = 1000
+"#.to_string()
+}
+
+fn generate_omnixtracker_mod_content() -> String {
+    r#"// src/omnixtracker/mod.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXTRACKER]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+pub mod omnixerror;
+pub mod omnixlimit;
+pub mod omnixmetry;
+pub mod omnixpolicy;
+
+pub use omnixerror::{
+    BackoffStrategy, BulkheadConfig, CircuitBreakerPolicy, CircuitEvent, CircuitSnapshot, CircuitState, Clock,
+    ErrorCategory, ErrorLogLimiter, ErrorReport, ErrorSeverity, LogContext, ManualClock, OmniXContext, OmniXError,
+    OmniXErrorManager, OmniXErrorManagerConfig, ProblemDetails, RetryClass, SlidingWindow, SystemClock, DEFAULT_CIRCUIT,
+};
+pub use omnixlimit::{OmniXRateLimiter, RateDecision, RateLimitAlgorithm, RateLimitConfig};
+pub use omnixmetry::{setup_global_subscriber, LabelRegistry, OmniXMetry, OVERFLOW_LABEL_VALUE};
+pub use omnixpolicy::{validate_config, ResiliencePolicies, DEFAULT_POLICY, POLICY_RELOAD_INTERVAL, RESILIENCE_CONFIG_FILE};
+pub use tokio_util::sync::CancellationToken;
+"#.to_string()
+}
+
+fn generate_lxsl_content() -> String {
+    r##"// src/utils/lxsl.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[LXSL]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+use super::langreg::{HeaderPlacement, LanguageRegistry, LANGUAGES_CONFIG_FILE};
+use super::xyntag::{signature_text, TagPolicy};
+use std::collections::hash_map::DefaultHasher;
+use std::io::{self, BufRead, Read, Write};
+use std::hash::{Hash, Hasher};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use std::time::UNIX_EPOCH;
+use rayon::prelude::*;
+use walkdir::WalkDir;
+use std::fmt;
+
+pub const LXSL_CACHE_FILE: &str = "target/.lxsl-cache";
+pub const LXSL_MAX_FILE_SIZE: u64 = 4 * 1024 * 1024;
+pub const LXSL_SNIFF_BYTES: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LxslOutcome {
+    Stamped,
+    Unchanged,
+    Cached,
+    Skipped,
+    SkippedBinary,
+    SkippedTooLarge,
+}
+
+#[derive(Debug, Default)]
+pub struct LxslReport {
+    pub outcomes: Vec<(PathBuf, LxslOutcome)>,
+    pub errors: Vec<(PathBuf, io::Error)>,
+}
+
+impl LxslReport {
+    pub fn count(&self, outcome: LxslOutcome) -> usize {
+        self.outcomes.iter().filter(|(_, o)| *o == outcome).count()
+    }
+}
+
+impl fmt::Display for LxslReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stamped: {}, unchanged: {}, cached: {}, skipped: {}, binary: {}, too large: {}, errors: {}",
+            self.count(LxslOutcome::Stamped),
+            self.count(LxslOutcome::Unchanged),
+            self.count(LxslOutcome::Cached),
+            self.count(LxslOutcome::Skipped),
+            self.count(LxslOutcome::SkippedBinary),
+            self.count(LxslOutcome::SkippedTooLarge),
+            self.errors.len()
+        )
+    }
+}
+
+// Cache key material: a file is untouched if its mtime, size and expected signature all match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CacheEntry {
+    modified: u128,
+    len: u64,
+    signature_hash: u64,
+}
+
+impl CacheEntry {
+    fn read(path: &Path, signature_hash: u64) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        Ok(Self {
+            modified,
+            len: metadata.len(),
+            signature_hash,
+        })
+    }
+}
+
+pub struct LordXynSignatureLine;
+
+impl LordXynSignatureLine {
+    pub fn generate_signature_line(file_path: &str) -> String {
+        Self::generate_signature_line_with(file_path, TagPolicy::global())
+    }
+
+    pub fn generate_signature_line_with(file_path: &str, policy: TagPolicy) -> String {
+        Self::render_signature_line(file_path, LanguageRegistry::global(), policy)
+    }
+
+    fn render_signature_line(file_path: &str, registry: &LanguageRegistry, policy: TagPolicy) -> String {
+        let normalized_path = file_path.replace('\\', "/");
+        let path = Path::new(&normalized_path);
+
+        match registry.lookup(path) {
+            Some(language) => language.comment(&signature_text(path, policy)),
+            None => String::new(),
+        }
+    }
+
+    pub fn enforce_signature_at_line_1(file_path: &str) -> io::Result<()> {
+        Self::enforce_signature(file_path).map(|_| ())
+    }
+
+    pub fn enforce_signature(file_path: &str) -> io::Result<LxslOutcome> {
+        if Self::should_skip_file(file_path) {
+            return Ok(LxslOutcome::Skipped);
+        }
+
+        let language = match LanguageRegistry::global().lookup(Path::new(file_path)) {
+            Some(language) => language,
+            None => return Ok(LxslOutcome::Skipped),
+        };
+
+        let signature = Self::generate_signature_line(file_path);
+        Self::write_signature(Path::new(file_path), &signature, language.placement, LanguageRegistry::global())
+    }
+
+    // Walks `root` in parallel, stamping only files whose first line is out of date.
+    // Files unchanged since the last run (per the mtime cache) are not opened at all.
+    // Languages and the tag policy come from `root`, not the working directory.
+    pub fn enforce_signatures(root: &Path) -> io::Result<LxslReport> {
+        let registry = LanguageRegistry::load(&root.join(LANGUAGES_CONFIG_FILE))?;
+        let policy = TagPolicy::load(root);
+        let cache_path = root.join(LXSL_CACHE_FILE);
+        let cache = Self::load_cache(&cache_path);
+
+        let files: Vec<PathBuf> = WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_str().unwrap_or("");
+                e.depth() == 0 || !(name.starts_with('.') || name == "target")
+            })
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect();
+
+        let results: Vec<_> = files
+            .into_par_iter()
+            .map(|path| {
+                let relative = path
+                    .strip_prefix(root)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let result = Self::enforce_cached(&path, &relative, cache.get(&relative), &registry, policy);
+                (path, relative, result)
+            })
+            .collect();
+
+        let mut report = LxslReport::default();
+        let mut new_cache = HashMap::new();
+        for (path, relative, result) in results {
+            match result {
+                Ok((outcome, entry)) => {
+                    if let Some(entry) = entry {
+                        new_cache.insert(relative, entry);
+                    }
+                    report.outcomes.push((path, outcome));
+                }
+                Err(e) => report.errors.push((path, e)),
+            }
+        }
+
+        Self::save_cache(&cache_path, &new_cache)?;
+        Ok(report)
+    }
+
+    fn enforce_cached(
+        path: &Path,
+        relative: &str,
+        cached: Option<&CacheEntry>,
+        registry: &LanguageRegistry,
+        policy: TagPolicy,
+    ) -> io::Result<(LxslOutcome, Option<CacheEntry>)> {
+        if Self::should_skip_file(relative) {
+            return Ok((LxslOutcome::Skipped, None));
+        }
+
+        let language = match registry.lookup(Path::new(relative)) {
+            Some(language) => language,
+            None => return Ok((LxslOutcome::Skipped, None)),
+        };
+
+        let signature = Self::render_signature_line(relative, registry, policy);
+        let mut hasher = DefaultHasher::new();
+        signature.hash(&mut hasher);
+        let signature_hash = hasher.finish();
+
+        let before = CacheEntry::read(path, signature_hash)?;
+        if cached == Some(&before) {
+            return Ok((LxslOutcome::Cached, Some(before)));
+        }
+
+        let outcome = Self::write_signature(path, &signature, language.placement, registry)?;
+        match outcome {
+            // Re-sniff these every run so a file that becomes text gets stamped
+            LxslOutcome::SkippedBinary | LxslOutcome::SkippedTooLarge => Ok((outcome, None)),
+            _ => Ok((outcome, Some(CacheEntry::read(path, signature_hash)?))),
+        }
+    }
+
+    fn write_signature(
+        path: &Path,
+        signature: &str,
+        placement: HeaderPlacement,
+        registry: &LanguageRegistry,
+    ) -> io::Result<LxslOutcome> {
+        if let Some(skipped) = Self::sniff_file(path)? {
+            return Ok(skipped);
+        }
+
+        let mut reader = io::BufReader::new(OpenOptions::new().read(true).open(path)?);
+        let mut first_line = String::new();
+        reader.read_line(&mut first_line)?;
+        let header_index = placement.header_line_index(first_line.trim_end());
+        let mut header_line = first_line;
+        if header_index > 0 {
+            header_line.clear();
+            reader.read_line(&mut header_line)?;
+        }
+        if header_line.trim_end() == signature {
+            return Ok(LxslOutcome::Unchanged);
+        }
+
+        let file = OpenOptions::new().read(true).open(path)?;
+        let lines: Vec<String> = io::BufReader::new(file).lines().collect::<Result<_, _>>()?;
+
+        // Shebangs and declarations stay above the signature
+        let (prologue, body) = lines.split_at(header_index.min(lines.len()));
+        let cleaned_lines: Vec<&String> = body
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, line)| {
+                if idx + header_index < 10
+                    && (Self::is_invalid_xyn_signature(line)
+                        || Self::is_xyn_signature(line)
+                        || Self::is_registered_signature(registry, line))
+                {
+                    None
+                } else {
+                    Some(line)
+                }
+            })
+            .collect();
+
+        let mut file = OpenOptions::new().write(true).truncate(true).open(path)?;
+        for line in prologue {
+            writeln!(file, "{}", line)?;
+        }
+        writeln!(file, "{}", signature)?;
+
+        for line in cleaned_lines {
+            writeln!(file, "{}", line)?;
+        }
 
-            let log_entry = format!(
-                "{} [{}] {}: {}",
-                Local::now().format("%B, %d %Y @ %I:%M %p"),
-                level_str,
-                event.metadata().target(),
-                fields
-            );
+        Ok(LxslOutcome::Stamped)
+    }
+
+    // Returns the skip outcome for files that must not be read as text
+    pub fn sniff_file(path: &Path) -> io::Result<Option<LxslOutcome>> {
+        let len = fs::metadata(path)?.len();
+        if len > LXSL_MAX_FILE_SIZE {
+            return Ok(Some(LxslOutcome::SkippedTooLarge));
+        }
+
+        let mut buffer = Vec::with_capacity(LXSL_SNIFF_BYTES);
+        fs::File::open(path)?
+            .take(LXSL_SNIFF_BYTES as u64)
+            .read_to_end(&mut buffer)?;
+
+        // Complete when the window reached end of file, including files of exactly LXSL_SNIFF_BYTES
+        if Self::is_binary_content(&buffer, len <= LXSL_SNIFF_BYTES as u64) {
+            Ok(Some(LxslOutcome::SkippedBinary))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn is_binary_content(bytes: &[u8], complete: bool) -> bool {
+        if bytes.contains(&0) {
+            return true;
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(_) => false,
+            // A multi-byte character cut off by the sniff window is still text
+            Err(e) => complete || e.error_len().is_some(),
+        }
+    }
+
+    fn load_cache(cache_path: &Path) -> HashMap<String, CacheEntry> {
+        let content = fs::read_to_string(cache_path).unwrap_or_default();
+        content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, '\t');
+                let modified = fields.next()?.parse().ok()?;
+                let len = fields.next()?.parse().ok()?;
+                let signature_hash = fields.next()?.parse().ok()?;
+                let relative = fields.next()?.to_string();
+                Some((relative, CacheEntry { modified, len, signature_hash }))
+            })
+            .collect()
+    }
+
+    fn save_cache(cache_path: &Path, cache: &HashMap<String, CacheEntry>) -> io::Result<()> {
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = io::BufWriter::new(fs::File::create(cache_path)?);
+        for (relative, entry) in cache {
+            writeln!(file, "{}\t{}\t{}\t{}", entry.modified, entry.len, entry.signature_hash, relative)?;
+        }
+        file.flush()
+    }
+
+    pub fn get_comment_prefix(extension: &str) -> &'static str {
+        LanguageRegistry::global()
+            .lookup_extension(extension)
+            .map_or("", |language| language.comment_start.as_str())
+    }
+
+    pub fn is_invalid_xyn_signature(line: &str) -> bool {
+        let has_valid_path = line.contains("// src") || line.contains("// build") || line.contains("// tests");
+        let has_signature_format = line.contains("~=#######D]") && line.contains("<Lord[") && line.contains("]Xyn>");
+        has_valid_path && !has_signature_format
+    }
+
+    pub fn is_xyn_signature(line: &str) -> bool {
+        let has_valid_path = line.contains("// src") || line.contains("// build") || line.contains("// tests");
+        let has_signature_format = line.contains("~=#######D]") && line.contains("<Lord[") && line.contains("]Xyn>");
+        has_valid_path && has_signature_format
+    }
+
+    // A signature in any registered comment style, e.g. `# src/...` in a Python file
+    fn is_registered_signature(registry: &LanguageRegistry, line: &str) -> bool {
+        registry.is_comment_line(line)
+            && line.contains("~=#######D]======A===r===c====M===o===o===n=====<Lord[")
+            && line.contains("]Xyn>=====S===t===u===d===i===o===s======[R|$>")
+    }
+
+    pub fn should_skip_file(file_path: &str) -> bool {
+        let skip_extensions = [
+            "lock", "log", "png", "jpg", "jpeg", "gif", "pyc", "toml", "exe", "dll", "so", "bin",
+            "wasm", "pdf", "zip", "gz", "tar", "ttf", "otf", "woff", "woff2", "ico",
+        ];
+
+        Path::new(file_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map_or(false, |ext| skip_extensions.contains(&ext))
+    }
+}
+"##.to_string()
+}
+
+fn generate_utils_mod_content() -> String {
+    r#"// src/utils/mod.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[UTILS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+// build.rs and xtask compile these three files directly via `#[path]`, so they may only depend on each other
+// (through `super::`) and on external crates, never on anything else in this crate
+pub mod langreg;
+pub mod lxsl;
+pub mod xyntag;
+
+pub use langreg::{HeaderPlacement, LanguageRegistry, LanguageSpec};
+pub use lxsl::{LordXynSignatureLine, LxslOutcome, LxslReport};
+pub use xyntag::TagPolicy;
+"#.to_string()
+}
+
+// Templates for src/main.rs and tests/ refer to the generated crate as `test6`; swap in the real crate name
+fn render_crate_template(template: &str, project_name: &str) -> String {
+    template.replace("test6::", &format!("{}::", project_name.replace('-', "_")))
+}
+
+fn generate_constants_tests_content(project_name: &str) -> String {
+    render_crate_template(r#"// tests/constants_tests.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[CONSTANTS-TESTS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+#[cfg(test)]
+mod tests {
+    use test6::constants::*;
+    use std::env;
+
+    #[test]
+    fn test_project_directories() {
+        let expected_directories = vec![
+            "src/omnixtracker",
+            "src/constants",
+            "src/utils",
+            "tests",
+            "Xdocs",
+            "Xtls",
+        ];
+        assert_eq!(PROJECT_DIRECTORIES.to_vec(), expected_directories);
+    }
+
+    #[test]
+    fn test_password_salt_length() {
+        assert_eq!(PASSWORD_SALT_LENGTH, 32);
+    }
+
+    #[test]
+    fn test_password_hash_iterations() {
+        assert_eq!(PASSWORD_HASH_ITERATIONS, 100_000);
+    }
+
+    #[test]
+    fn test_jwt_expiration() {
+        assert_eq!(JWT_EXPIRATION, 3600);
+    }
+
+    #[test]
+    fn test_rate_limit_window() {
+        assert_eq!(RATE_LIMIT_WINDOW, 60);
+    }
+
+    #[test]
+    fn test_rate_limit_max_requests() {
+        assert_eq!(RATE_LIMIT_MAX_REQUESTS, 100);
+    }
+
+    #[test]
+    fn test_rate_limit_env_defaults() {
+        env::remove_var("RATE_LIMIT_WINDOW");
+        env::remove_var("RATE_LIMIT_MAX_REQUESTS");
+        assert_eq!(get_rate_limit_window(), RATE_LIMIT_WINDOW);
+        assert_eq!(get_rate_limit_max_requests(), RATE_LIMIT_MAX_REQUESTS);
+    }
+
+    #[test]
+    fn test_enable_experimental_features() {
+        assert_eq!(ENABLE_EXPERIMENTAL_FEATURES, false);
+    }
+
+    #[test]
+    fn test_use_legacy_auth() {
+        assert_eq!(USE_LEGACY_AUTH, false);
+    }
+
+    #[test]
+    fn test_prometheus_listener_default() {
+        env::remove_var("PROMETHEUS_LISTENER");
+        assert_eq!(&*PROMETHEUS_LISTENER, "0.0.0.0:9001");
+    }
+
+    #[test]
+    fn test_initial_log_level_default() {
+        env::remove_var("INITIAL_LOG_LEVEL");
+        assert_eq!(*INITIAL_LOG_LEVEL, tracing::Level::INFO);
+    }
+
+    #[test]
+    fn test_log_file_path_default() {
+        env::remove_var("LOG_FILE_PATH");
+        assert_eq!(&*LOG_FILE_PATH, "xynpro.log");
+    }
+
+    #[test]
+    fn test_git_remote_default() {
+        env::remove_var("GIT_REMOTE");
+        assert_eq!(&*GIT_REMOTE, "origin");
+    }
+
+    #[test]
+    fn test_git_branch_default() {
+        env::remove_var("GIT_BRANCH");
+        assert_eq!(&*GIT_BRANCH, "main");
+    }
+
+    #[test]
+    fn test_git_commit_message_default() {
+        env::remove_var("GIT_COMMIT_MESSAGE");
+        assert_eq!(&*GIT_COMMIT_MESSAGE, "Automated update via xyngit");
+    }
+
+    #[test]
+    fn test_circuit_breaker_threshold_default() {
+        env::remove_var("CIRCUIT_BREAKER_THRESHOLD");
+        assert_eq!(*CIRCUIT_BREAKER_THRESHOLD, 10);
+    }
+
+    #[test]
+    fn test_circuit_breaker_duration_default() {
+        env::remove_var("CIRCUIT_BREAKER_DURATION");
+        assert_eq!(*CIRCUIT_BREAKER_DURATION, std::time::Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_base_delay_default() {
+        env::remove_var("BASE_DELAY");
+        assert_eq!(*BASE_DELAY, std::time::Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_max_delay_default() {
+        env::remove_var("MAX_DELAY");
+        assert_eq!(*MAX_DELAY, std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_default_timeout_default() {
+        env::remove_var("DEFAULT_TIMEOUT");
+        assert_eq!(*DEFAULT_TIMEOUT, std::time::Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_error_log_window_default() {
+        env::remove_var("ERROR_LOG_WINDOW");
+        assert_eq!(*ERROR_LOG_WINDOW, std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_max_retries_default() {
+        env::remove_var("MAX_RETRIES");
+        assert_eq!(get_max_retries(), 3);
+    }
+}
+"#, project_name)
+}
+
+fn generate_omnixerror_tests_content(project_name: &str) -> String {
+    render_crate_template(r#"// tests/omnixerror_tests.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXERROR-TESTS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+#[cfg(test)]
+mod tests {
+    use test6::omnixtracker::{
+        BackoffStrategy, BulkheadConfig, CancellationToken, CircuitBreakerPolicy, CircuitEvent, CircuitState, ErrorCategory,
+        ErrorLogLimiter, ErrorReport, ErrorSeverity, ManualClock, OmniXContext, OmniXError, OmniXErrorManager, OmniXErrorManagerConfig,
+        OmniXMetry, RetryClass, SlidingWindow,
+    };
+    use std::sync::Arc;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::error::Error;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_operation_failed_error() {
+        let error = OmniXError::OperationFailed { 
+            operation: "test".to_string(),
+            details: "Some error occurred".to_string() 
+        };
+        assert_eq!(format!("{}", error), "Operation failed during test: Some error occurred");
+    }
+
+    #[test]
+    fn test_retry_limit_exceeded_error() {
+        let error = OmniXError::RetryLimitExceeded {
+            retries: 5,
+            last_error: "Last attempt failed".to_string(),
+        };
+        assert_eq!(format!("{}", error), "Retry limit exceeded after 5 attempts: Last attempt failed");
+    }
+
+    #[test]
+    fn test_circuit_breaker_activated_error() {
+        let error = OmniXError::CircuitBreakerActivated {
+            count: 10,
+            duration: Duration::from_secs(60),
+        };
+        assert_eq!(format!("{}", error), "Circuit breaker activated after 10 errors in 60s");
+    }
+
+    #[test]
+    fn test_operation_timeout_error() {
+        let error = OmniXError::OperationTimeout {
+            duration: Duration::from_secs(30),
+        };
+        assert_eq!(format!("{}", error), "Operation timed out after 30s");
+    }
+
+    #[test]
+    fn test_file_system_error() {
+        let error = OmniXError::FileSystemError("Disk not found".to_string());
+        assert_eq!(format!("{}", error), "File system error: Disk not found");
+    }
+
+    #[test]
+    fn test_env_var_error() {
+        let error = OmniXError::EnvVarError("Missing environment variable".to_string());
+        assert_eq!(format!("{}", error), "Environment variable error: Missing environment variable");
+    }
 
-            println!("{}", log_entry);
+    #[test]
+    fn test_project_creation_error() {
+        let error = OmniXError::ProjectCreationError("Unable to create project".to_string());
+        assert_eq!(format!("{}", error), "Project creation error: Unable to create project");
+    }
 
-            if let Err(e) = self.write_log(&log_entry) {
-                eprintln!("Failed to write to log file: {}", e);
-            }
-        }
+    #[test]
+    fn test_metrics_init_error() {
+        let error = OmniXError::MetricsInitError("Failed to initialize metrics".to_string());
+        assert_eq!(format!("{}", error), "Metrics initialization error: Failed to initialize metrics");
     }
 
-    fn enabled(
-        &self,
-        metadata: &Metadata<'_>,
-        _: tracing_subscriber::layer::Context<'_, S>,
-    ) -> bool {
-        *metadata.level() <= self.get_log_level()
+    #[test]
+    fn test_logging_error() {
+        let error = OmniXError::LoggingError("Log file not found".to_string());
+        assert_eq!(format!("{}", error), "Logging error: Log file not found");
     }
-}
 
-struct FieldVisitor<'a> {
-    output: &'a mut String,
-}
+    #[test]
+    fn test_database_error() {
+        let error = OmniXError::DatabaseError("Connection failed".to_string());
+        assert_eq!(format!("{}", error), "Database error: Connection failed");
+    }
 
-impl<'a> tracing::field::Visit for FieldVisitor<'a> {
-    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
-        if !self.output.is_empty() {
-            self.output.push_str(", ");
-        }
-        let _ = write!(self.output, "{} = {:?}", field.name(), value);
+    #[test]
+    fn test_network_error() {
+        let error = OmniXError::NetworkError("Network unreachable".to_string());
+        assert_eq!(format!("{}", error), "Network error: Network unreachable");
     }
-}
 
-pub fn setup_global_subscriber(omnixmetry: OmniXMetry) -> AnyhowResult<()> {
-    let env_filter = EnvFilter::try_from_default_env().or_else(|_| EnvFilter::try_new("info"))?;
-    let subscriber = Registry::default().with(env_filter).with(omnixmetry);
-    tracing::subscriber::set_global_default(subscriber).context("Failed to set global subscriber")?;
-    Ok(())
-}
-"#.to_string()
-}
+    #[test]
+    fn test_authentication_error() {
+        let error = OmniXError::AuthenticationError("Invalid credentials".to_string());
+        assert_eq!(format!("{}", error), "Authentication error: Invalid credentials");
+    }
 
-fn generate_lxsl_content() -> String {
-    r##"// src/utils/lxsl.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[UTILS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+    #[test]
+    fn test_authorization_error() {
+        let error = OmniXError::AuthorizationError("Access denied".to_string());
+        assert_eq!(format!("{}", error), "Authorization error: Access denied");
+    }
 
-use crate::constants::ARCMOON_SIGNATURE;
-use std::fs::OpenOptions;
-use std::io::{self, BufRead, Write};
-use std::path::Path;
+    #[test]
+    fn test_validation_error() {
+        let error = OmniXError::ValidationError("Input is invalid".to_string());
+        assert_eq!(format!("{}", error), "Validation error: Input is invalid");
+    }
 
-pub struct LordXynSignatureLine;
+    #[test]
+    fn test_error_codes_are_stable() {
+        assert_eq!(OmniXError::OperationFailed { operation: "op".to_string(), details: "d".to_string() }.code(), "XYN-E0001");
+        assert_eq!(OmniXError::ProjectCreationError("x".to_string()).code(), "XYN-E0007");
+        assert_eq!(OmniXError::ValidationError("x".to_string()).code(), "XYN-E0014");
+    }
 
-impl LordXynSignatureLine {
-    pub fn generate_signature_line(file_path: &str) -> String {
-        let normalized_path = Path::new(file_path)
-            .to_str()
-            .unwrap_or(file_path)
-            .replace('\\', "/");
+    #[test]
+    fn test_error_codes_are_unique() {
+        let errors = vec![
+            OmniXError::OperationFailed { operation: "op".to_string(), details: "d".to_string() },
+            OmniXError::RetryLimitExceeded { retries: 1, last_error: "e".to_string() },
+            OmniXError::CircuitBreakerActivated { count: 1, duration: Duration::from_secs(1) },
+            OmniXError::OperationTimeout { duration: Duration::from_secs(1) },
+            OmniXError::FileSystemError("x".to_string()),
+            OmniXError::EnvVarError("x".to_string()),
+            OmniXError::ProjectCreationError("x".to_string()),
+            OmniXError::MetricsInitError("x".to_string()),
+            OmniXError::LoggingError("x".to_string()),
+            OmniXError::DatabaseError("x".to_string()),
+            OmniXError::NetworkError("x".to_string()),
+            OmniXError::AuthenticationError("x".to_string()),
+            OmniXError::AuthorizationError("x".to_string()),
+            OmniXError::ValidationError("x".to_string()),
+            OmniXError::ConfigError("x".to_string()),
+            OmniXError::BulkheadRejected { operation: "op".to_string(), max_concurrent: 1, max_queued: 0 },
+            OmniXError::RateLimited { key: "k".to_string(), retry_after: Duration::from_secs(1) },
+            OmniXError::DeadlineExceeded { deadline: Duration::from_secs(1), attempts: 1 },
+            OmniXError::Cancelled { operation: "op".to_string(), attempts: 1 },
+        ];
+        let codes: std::collections::HashSet<_> = errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes.len(), errors.len(), "Every variant should have its own code");
+    }
+
+    #[test]
+    fn test_error_categories_and_severities() {
+        assert_eq!(OmniXError::FileSystemError("x".to_string()).category(), ErrorCategory::Io);
+        assert_eq!(OmniXError::EnvVarError("x".to_string()).category(), ErrorCategory::Config);
+        assert_eq!(OmniXError::NetworkError("x".to_string()).category(), ErrorCategory::Network);
+        assert_eq!(OmniXError::AuthorizationError("x".to_string()).category(), ErrorCategory::Auth);
+        assert_eq!(OmniXError::ValidationError("x".to_string()).category(), ErrorCategory::Validation);
+        assert_eq!(OmniXError::OperationTimeout { duration: Duration::from_secs(1) }.category(), ErrorCategory::Resilience);
+        assert_eq!(ErrorCategory::Resilience.to_string(), "resilience");
+
+        assert_eq!(OmniXError::DatabaseError("x".to_string()).severity(), ErrorSeverity::Critical);
+        assert_eq!(OmniXError::OperationTimeout { duration: Duration::from_secs(1) }.severity(), ErrorSeverity::Warning);
+        assert!(ErrorSeverity::Critical > ErrorSeverity::Error);
+    }
 
-        let path_parts: Vec<&str> = normalized_path.split('/').collect();
+    #[test]
+    fn test_git_error_keeps_source() {
+        let error: OmniXError = git2::Error::from_str("remote rejected").into();
+        assert_eq!(error.code(), "XYN-E0001");
+        assert!(error.to_string().starts_with("Operation failed during Git operation failed"));
+        let source = error.source().expect("Git error should be kept as the source");
+        assert!(source.downcast_ref::<git2::Error>().is_some());
+    }
 
-        let extension = Path::new(file_path)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("");
+    #[test]
+    fn test_caused_message_does_not_repeat_its_source() {
+        let error: OmniXError = std::io::Error::new(std::io::ErrorKind::NotFound, "missing").into();
+        assert_eq!(error.to_string(), "File system error: missing");
+        let chain: Vec<String> = error.chain().map(|e| e.to_string()).collect();
+        assert_eq!(chain, vec!["File system error: missing", "missing"], "The source text should appear once, as its own link");
+    }
 
-        let comment_prefix = Self::get_comment_prefix(extension);
+    #[test]
+    fn test_anyhow_round_trip_preserves_omnix_error() {
+        let original = anyhow::Error::new(OmniXError::NetworkError("unreachable".to_string()));
+        let error: OmniXError = original.into();
+        assert!(matches!(error, OmniXError::NetworkError(_)));
 
-        if comment_prefix.is_empty() {
-            return String::new();
-        }
+        let error: OmniXError = anyhow::anyhow!("disk full").context("writing report").into();
+        assert_eq!(error.chain().count(), 3, "anyhow context and cause should both be kept");
+    }
 
-        let signature_path = Self::build_signature_path(&path_parts);
-        let xyn_signature = Self::build_xyn_signature(&path_parts);
+    #[test]
+    fn test_context_wraps_and_delegates_classification() {
+        let result: Result<(), OmniXError> = Err(OmniXError::FileSystemError("missing".to_string()));
+        let error = result
+            .context("loading config/default.toml")
+            .with_context(|| format!("starting {}", "xynpro"))
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "starting xynpro");
+        assert_eq!(error.code(), "XYN-E0005");
+        assert_eq!(error.category(), ErrorCategory::Io);
+        assert!(matches!(error.root(), OmniXError::FileSystemError(_)));
+
+        let chain: Vec<String> = error.chain().map(|e| e.to_string()).collect();
+        assert_eq!(chain, vec!["starting xynpro", "loading config/default.toml", "File system error: missing"]);
+    }
 
-        format!(
-            "{} {} {}",
-            comment_prefix,
-            signature_path,
-            ARCMOON_SIGNATURE.replace("{}", &xyn_signature)
+    #[test]
+    fn test_error_report_json_round_trip() {
+        let error = OmniXError::caused_by(
+            OmniXError::OperationFailed {
+                operation: "sync".to_string(),
+                details: "remote hung up".to_string(),
+            },
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "broken pipe"),
         )
+        .context("pushing release tag");
+
+        let report = error.report();
+        assert_eq!(report.code, "XYN-E0001");
+        assert_eq!(report.category, ErrorCategory::Internal);
+        assert_eq!(report.severity, ErrorSeverity::Error);
+        assert_eq!(report.message, "Operation failed during sync: remote hung up");
+        assert_eq!(report.context, vec!["pushing release tag"]);
+        assert_eq!(report.causes, vec!["broken pipe"]);
+        assert_eq!(report.fields.get("operation").map(String::as_str), Some("sync"));
+
+        let json = report.to_json().expect("report should serialize");
+        assert!(json.contains("\"category\":\"internal\""));
+        let restored = ErrorReport::from_json(&json).expect("report should deserialize");
+        assert_eq!(restored, report);
     }
 
-    pub fn enforce_signature_at_line_1(file_path: &str) -> io::Result<()> {
-        let path = Path::new(file_path);
-
-        if Self::should_skip_file(file_path) {
-            return Ok(());
-        }
+    #[test]
+    fn test_error_report_problem_details() {
+        let report = ErrorReport::from(OmniXError::OperationTimeout { duration: Duration::from_millis(250) });
+        assert_eq!(report.fields.get("duration_ms").map(String::as_str), Some("250"));
+
+        let problem = report.to_problem_details(Some("/jobs/42"));
+        assert_eq!(problem.problem_type, "urn:xyn:error:xyn-e0004");
+        assert_eq!(problem.status, 504);
+        assert_eq!(problem.detail, report.message);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&report.to_problem_json(Some("/jobs/42")).unwrap()).unwrap();
+        assert_eq!(json["type"], "urn:xyn:error:xyn-e0004");
+        assert_eq!(json["title"], "resilience error");
+        assert_eq!(json["instance"], "/jobs/42");
+        assert_eq!(json["code"], "XYN-E0004");
+        assert!(json.get("context").is_none());
+
+        assert_eq!(ErrorReport::from(OmniXError::ValidationError("bad".into())).http_status(), 400);
+        assert_eq!(ErrorReport::from(OmniXError::AuthorizationError("no".into())).http_status(), 403);
+    }
 
-        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
-        let comment_prefix = Self::get_comment_prefix(extension);
+    #[test]
+    fn test_error_log_limiter_collapses_repeats() {
+        let clock = Arc::new(ManualClock::new());
+        let limiter = ErrorLogLimiter::with_clock(Duration::from_millis(50), clock.clone());
+        assert_eq!(limiter.admit(1), Some(0));
+        assert_eq!(limiter.admit(1), None);
+        assert_eq!(limiter.admit(1), None);
+        assert_eq!(limiter.admit(2), Some(0), "Different errors are limited independently");
+
+        clock.advance(Duration::from_millis(60));
+        assert_eq!(limiter.admit(1), Some(2), "Next logged occurrence should report the swallowed repeats");
+        assert_eq!(limiter.admit(1), None);
+
+        let unlimited = ErrorLogLimiter::new(Duration::ZERO);
+        assert_eq!(unlimited.admit(1), Some(0));
+        assert_eq!(unlimited.admit(1), Some(0));
+    }
 
-        if comment_prefix.is_empty() {
-            return Ok(());
+    #[test]
+    fn test_error_log_limiter_evicts_oldest_when_full() {
+        let clock = Arc::new(ManualClock::new());
+        let limiter = ErrorLogLimiter::with_clock(Duration::from_secs(60), clock.clone());
+        for key in 0..1024 {
+            assert_eq!(limiter.admit(key), Some(0));
+            clock.advance(Duration::from_millis(1));
         }
 
-        let file = OpenOptions::new().read(true).open(&path)?;
-        let lines: Vec<String> = io::BufReader::new(file).lines().collect::<Result<_, _>>()?;
+        assert_eq!(limiter.admit(5000), Some(0), "A new key is admitted even when every entry is inside the window");
+        assert_eq!(limiter.admit(1), None, "Recent keys keep being suppressed");
+        assert_eq!(limiter.admit(0), Some(0), "The oldest key was evicted to make room");
+    }
 
-        let cleaned_lines: Vec<String> = lines
-            .into_iter()
-            .enumerate()
-            .filter_map(|(idx, line)| {
-                if idx < 10 && (Self::is_invalid_xyn_signature(&line) || Self::is_xyn_signature(&line)) {
-                    None
-                } else {
-                    Some(line)
-                }
-            })
-            .collect();
+    fn parse_port(raw: &str) -> Result<u16, OmniXError> {
+        Ok(raw.trim().parse::<u16>()?)
+    }
 
-        let signature = Self::generate_signature_line(file_path);
-        let mut file = OpenOptions::new().write(true).truncate(true).open(path)?;
+    fn read_config(path: &std::path::Path) -> Result<toml::Value, OmniXError> {
+        let raw = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&raw)?)
+    }
 
-        if !signature.is_empty() {
-            writeln!(file, "{}", signature)?;
-        }
+    #[test]
+    fn test_question_mark_conversions() {
+        let error = parse_port("http").unwrap_err();
+        assert_eq!(error.code(), "XYN-E0014");
+        assert_eq!(error.retry_class(), RetryClass::Permanent);
+        assert!(error.source().unwrap().downcast_ref::<std::num::ParseIntError>().is_some());
+
+        let error = read_config(std::path::Path::new("does/not/exist.toml")).unwrap_err();
+        assert!(matches!(error.root(), OmniXError::FileSystemError(_)));
+        assert!(error.source().unwrap().downcast_ref::<std::io::Error>().is_some());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.toml");
+        std::fs::write(&path, "retries = [").unwrap();
+        let error = read_config(&path).unwrap_err();
+        assert_eq!(error.code(), "XYN-E0015");
+        assert_eq!(error.category(), ErrorCategory::Config);
+
+        let error: OmniXError = std::env::var("XYN_SURELY_UNSET_VARIABLE").unwrap_err().into();
+        assert!(matches!(error.root(), OmniXError::EnvVarError(_)));
+    }
 
-        for line in cleaned_lines {
-            writeln!(file, "{}", line)?;
-        }
+    #[test]
+    fn test_io_error_kinds_map_to_variants() {
+        let refused: OmniXError = std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into();
+        assert!(matches!(refused.root(), OmniXError::NetworkError(_)));
+        let missing: OmniXError = std::io::Error::from(std::io::ErrorKind::NotFound).into();
+        assert!(matches!(missing.root(), OmniXError::FileSystemError(_)));
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn test_tokio_errors_convert() {
+        let join_error = tokio::spawn(async { panic!("boom") }).await.unwrap_err();
+        let error: OmniXError = join_error.into();
+        assert!(error.to_string().starts_with("Operation failed during Task panicked"));
+
+        let elapsed = tokio::time::timeout(Duration::from_millis(1), std::future::pending::<()>())
+            .await
+            .unwrap_err();
+        let error: OmniXError = elapsed.into();
+        assert_eq!(error.code(), "XYN-E0004");
+        assert_eq!(error.retry_class(), RetryClass::Retryable);
+        assert!(matches!(error.root(), OmniXError::OperationTimeout { duration } if duration.is_zero()), "The limit is unknown");
     }
 
-    pub fn build_signature_path(path_parts: &[&str]) -> String {
-        if path_parts.len() > 1 {
-            path_parts
-                .iter()
-                .take(path_parts.len() - 1)
-                .map(|comp| format!("{}/", comp))
-                .collect()
-        } else {
-            String::new()
+    fn fast_retry_config() -> OmniXErrorManagerConfig {
+        OmniXErrorManagerConfig {
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            ..OmniXErrorManagerConfig::default()
         }
     }
 
-    pub fn build_xyn_signature(path_parts: &[&str]) -> String {
-        path_parts
-            .last()
-            .map(|last_part| {
-                Path::new(last_part)
-                    .file_stem()
-                    .and_then(|stem| stem.to_str())
-                    .unwrap_or("UNKNOWN")
-                    .to_uppercase()
-                    .replace('_', "-")
-            })
-            .unwrap_or_else(|| "UNKNOWN".to_string())
+    #[tokio::test]
+    async fn test_permanent_errors_are_not_retried() {
+        let manager = OmniXErrorManager::new(fast_retry_config());
+        let metrics = OmniXMetry::detached();
+        let attempts = &AtomicUsize::new(0);
+
+        let result: Result<(), OmniXError> = manager
+            .handle_error(move || async move {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(OmniXError::ValidationError("bad input".to_string()))
+            }, &metrics)
+            .await;
+
+        assert!(matches!(result, Err(OmniXError::ValidationError(_))), "Permanent errors should be returned as-is");
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
     }
 
-    pub fn get_comment_prefix(extension: &str) -> &str {
-        match extension {
-            "rs" | "js" | "ts" | "cpp" | "c" | "java" => "//",
-            "py" | "sh" | "rb" | "pl" => "#",
-            "html" | "xml" => "<!--",
-            "css" | "scss" => "/*",
-            "sql" | "txt" => "--",
-            _ => "",
-        }
+    #[tokio::test]
+    async fn test_retryable_errors_use_all_attempts() {
+        let manager = OmniXErrorManager::new(fast_retry_config());
+        let metrics = OmniXMetry::detached();
+        let attempts = &AtomicUsize::new(0);
+
+        let result: Result<(), OmniXError> = manager
+            .handle_error(move || async move {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(OmniXError::NetworkError("unreachable".to_string()))
+            }, &metrics)
+            .await;
+
+        assert!(matches!(result, Err(OmniXError::RetryLimitExceeded { retries: 3, .. })));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
     }
 
-    pub fn is_invalid_xyn_signature(line: &str) -> bool {
-        let has_valid_path = line.contains("// src") || line.contains("// build") || line.contains("// tests");
-        let has_signature_format = line.contains("~=#######D]") && line.contains("<Lord[") && line.contains("]Xyn>");
-        has_valid_path && !has_signature_format
+    #[tokio::test]
+    async fn test_retry_overrides_from_config() {
+        let mut config = OmniXErrorManagerConfig {
+            max_delay: Duration::from_millis(100),
+            ..fast_retry_config()
+        };
+        config.retry_overrides.insert("XYN-E0011".to_string(), RetryClass::Permanent);
+        config.retry_overrides.insert("XYN-E0014".to_string(), RetryClass::RetryAfter(Duration::from_millis(50)));
+        let manager = OmniXErrorManager::new(config);
+        let metrics = OmniXMetry::detached();
+
+        let attempts = &AtomicUsize::new(0);
+        let _: Result<(), OmniXError> = manager
+            .handle_error(move || async move {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(OmniXError::NetworkError("unreachable".to_string()))
+            }, &metrics)
+            .await;
+        assert_eq!(attempts.load(Ordering::SeqCst), 1, "Overridden network errors should not be retried");
+
+        let attempts = &AtomicUsize::new(0);
+        let started = Instant::now();
+        let result = manager
+            .handle_error(move || async move {
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(OmniXError::ValidationError("not yet".to_string()))
+                } else {
+                    Ok(42)
+                }
+            }, &metrics)
+            .await;
+        assert_eq!(result.unwrap(), 42);
+        assert!(started.elapsed() >= Duration::from_millis(50), "Retry-after hint should be honoured");
     }
 
-    pub fn is_xyn_signature(line: &str) -> bool {
-        let has_valid_path = line.contains("// src") || line.contains("// build") || line.contains("// tests");
-        let has_signature_format = line.contains("~=#######D]") && line.contains("<Lord[") && line.contains("]Xyn>");
-        has_valid_path && has_signature_format
+    #[tokio::test]
+    async fn test_retry_after_hint_is_capped_by_max_delay_and_deadline() {
+        let clock = Arc::new(ManualClock::new());
+        let config = OmniXErrorManagerConfig {
+            max_retries: 2,
+            max_delay: Duration::from_secs(1),
+            ..OmniXErrorManagerConfig::default()
+        };
+        let manager = OmniXErrorManager::with_clock(config.clone(), clock.clone());
+        let metrics = OmniXMetry::detached();
+        let rate_limited = || async {
+            Err::<(), _>(OmniXError::RateLimited { key: "api".to_string(), retry_after: Duration::from_secs(3600) })
+        };
+
+        let result = manager.handle_operation("api.capped", rate_limited, &metrics).await;
+        assert!(matches!(result, Err(OmniXError::RetryLimitExceeded { retries: 2, .. })));
+        assert_eq!(clock.elapsed(), Duration::from_secs(1), "An hour-long hint should wait max_delay at most");
+
+        let manager = OmniXErrorManager::with_clock(OmniXErrorManagerConfig {
+            overall_timeout: Some(Duration::from_millis(500)),
+            ..config
+        }, clock.clone());
+        let result = manager.handle_operation("api.deadline", rate_limited, &metrics).await;
+        assert!(matches!(result, Err(OmniXError::DeadlineExceeded { attempts: 1, .. })));
+        assert_eq!(clock.elapsed(), Duration::from_secs(1), "No time should be spent sleeping towards a missed deadline");
     }
 
-    pub fn should_skip_file(file_path: &str) -> bool {
-        let skip_extensions = [
-            "lock", "log", "png", "jpg", "jpeg", "gif", "pyc", "toml", "exe", "dll", "so", "bin",
-        ];
+    async fn fail_network(manager: &OmniXErrorManager, key: &str, metrics: &OmniXMetry) -> Result<(), OmniXError> {
+        manager
+            .handle_operation(key, || async { Err(OmniXError::NetworkError("unreachable".to_string())) }, metrics)
+            .await
+    }
 
-        Path::new(file_path)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .map_or(false, |ext| skip_extensions.contains(&ext))
+    #[tokio::test]
+    async fn test_circuits_are_independent_per_operation() {
+        let manager = OmniXErrorManager::new(OmniXErrorManagerConfig {
+            circuit_breaker_threshold: 2,
+            ..fast_retry_config()
+        });
+        let metrics = OmniXMetry::detached();
+
+        let result = fail_network(&manager, "git.push", &metrics).await;
+        assert!(matches!(result, Err(OmniXError::CircuitBreakerActivated { count: 2, .. })));
+        assert!(matches!(manager.circuit("git.push").unwrap().state, CircuitState::Open(_)));
+
+        let result = manager.handle_operation("git.fetch", || async { Ok(7) }, &metrics).await;
+        assert_eq!(result.unwrap(), 7, "Other operations should not see the tripped breaker");
+        assert_eq!(manager.circuit("git.fetch").unwrap().state, CircuitState::Closed);
+        assert_eq!(manager.circuit_keys(), vec!["git.fetch".to_string(), "git.push".to_string()]);
+
+        let result = manager.handle_operation("git.push", || async { Ok(()) }, &metrics).await;
+        assert!(matches!(result, Err(OmniXError::CircuitBreakerActivated { .. })));
+
+        assert!(manager.reset_circuit("git.push"));
+        assert!(manager.circuit("git.push").is_none());
+        assert!(manager.handle_operation("git.push", || async { Ok(()) }, &metrics).await.is_ok());
+        assert!(!manager.reset_circuit("never.used"));
     }
-}
-"##.to_string()
-}
 
-fn generate_utils_mod_content() -> String {
-    r#"// src/utils/mod.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[UTILS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+    #[tokio::test]
+    async fn test_circuit_registry_is_bounded() {
+        let manager = OmniXErrorManager::new(OmniXErrorManagerConfig {
+            circuit_breaker_threshold: 1,
+            max_circuits: 2,
+            ..fast_retry_config()
+        });
+        let metrics = OmniXMetry::detached();
 
-pub mod lxsl;
+        let _ = fail_network(&manager, "tripped", &metrics).await;
+        manager.handle_operation("a", || async { Ok(()) }, &metrics).await.unwrap();
+        manager.handle_operation("b", || async { Ok(()) }, &metrics).await.unwrap();
 
-pub use lxsl::LordXynSignatureLine;
-"#.to_string()
-}
+        assert_eq!(manager.circuit_keys(), vec!["b".to_string(), "tripped".to_string()], "Closed circuits are evicted before open ones");
+    }
 
-fn generate_constants_tests_content(project_name: &str) -> String {
-    format!(r#"// tests/constants_tests.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[TESTS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+    fn failure_rate_config(slow_call_duration: Option<Duration>) -> OmniXErrorManagerConfig {
+        OmniXErrorManagerConfig {
+            max_retries: 1,
+            circuit_breaker_threshold: 100,
+            circuit_breaker_policy: CircuitBreakerPolicy::FailureRate {
+                window: SlidingWindow::Calls(4),
+                minimum_calls: 4,
+                failure_rate_threshold: 0.5,
+                slow_call_duration,
+                slow_call_rate_threshold: 0.5,
+            },
+            ..fast_retry_config()
+        }
+    }
 
-#[cfg(test)]
-mod tests {
-    use test6::constants::*;
-    use std::env;
+    #[tokio::test]
+    async fn test_failure_rate_policy_trips_on_ratio() {
+        let manager = OmniXErrorManager::new(failure_rate_config(None));
+        let metrics = OmniXMetry::detached();
+        let ok = || async { Ok::<(), OmniXError>(()) };
+
+        manager.handle_operation("db", ok, &metrics).await.unwrap();
+        let _ = fail_network(&manager, "db", &metrics).await;
+        manager.handle_operation("db", ok, &metrics).await.unwrap();
+        assert_eq!(manager.circuit("db").unwrap().state, CircuitState::Closed, "Below minimum_calls nothing trips");
+
+        let result = fail_network(&manager, "db", &metrics).await;
+        assert!(matches!(result, Err(OmniXError::CircuitBreakerActivated { count: 2, .. })), "2 of 4 calls failed");
+        let snapshot = manager.circuit("db").unwrap();
+        assert!(matches!(snapshot.state, CircuitState::Open(_)));
+        assert_eq!((snapshot.window_calls, snapshot.window_failures), (4, 2));
+    }
 
-    #[test]
-    fn test_project_directories() {
-        let expected_directories = vec![
-            "src/omnixtracker",
-            "src/constants",
-            "src/utils",
-            "tests",
-            "Xdocs",
-            "Xtls",
-        ];
-        assert_eq!(PROJECT_DIRECTORIES.to_vec(), expected_directories);
+    #[tokio::test]
+    async fn test_failure_rate_policy_ignores_interleaved_successes() {
+        let manager = OmniXErrorManager::new(failure_rate_config(None));
+        let metrics = OmniXMetry::detached();
+        for _ in 0..3 {
+            manager.handle_operation("db", || async { Ok(()) }, &metrics).await.unwrap();
+        }
+        let _ = fail_network(&manager, "db", &metrics).await;
+        assert_eq!(manager.circuit("db").unwrap().state, CircuitState::Closed, "1 of 4 is under the threshold");
     }
 
-    #[test]
-    fn test_password_salt_length() {
-        assert_eq!(PASSWORD_SALT_LENGTH, 32);
+    #[tokio::test]
+    async fn test_slow_call_rate_trips_circuit() {
+        let manager = OmniXErrorManager::new(failure_rate_config(Some(Duration::from_millis(20))));
+        let metrics = OmniXMetry::detached();
+        let slow = || async {
+            tokio::time::sleep(Duration::from_millis(25)).await;
+            Ok::<(), OmniXError>(())
+        };
+
+        for _ in 0..2 {
+            manager.handle_operation("search", || async { Ok(()) }, &metrics).await.unwrap();
+        }
+        manager.handle_operation("search", slow, &metrics).await.unwrap();
+        manager.handle_operation("search", slow, &metrics).await.expect("Slow calls still return their result");
+
+        let snapshot = manager.circuit("search").unwrap();
+        assert_eq!(snapshot.window_slow_calls, 2);
+        assert!(matches!(snapshot.state, CircuitState::Open(_)));
     }
 
-    #[test]
-    fn test_password_hash_iterations() {
-        assert_eq!(PASSWORD_HASH_ITERATIONS, 100_000);
+    #[tokio::test]
+    async fn test_half_open_trial_budget_and_success_threshold() {
+        let clock = Arc::new(ManualClock::new());
+        let manager = OmniXErrorManager::with_clock(OmniXErrorManagerConfig {
+            max_retries: 1,
+            circuit_breaker_threshold: 1,
+            half_open_max_trials: 2,
+            half_open_success_threshold: 2,
+            ..fast_retry_config()
+        }, clock.clone());
+        let metrics = OmniXMetry::detached();
+
+        let _ = fail_network(&manager, "api", &metrics).await;
+        let rejected = manager.handle_operation("api", || async { Ok(()) }, &metrics).await;
+        assert!(matches!(rejected, Err(OmniXError::CircuitBreakerActivated { .. })));
+
+        clock.advance(Duration::from_secs(60));
+        let gate = &tokio::sync::Semaphore::new(0);
+        let trial = move || async move {
+            let _permit = gate.acquire().await.unwrap();
+            Ok::<(), OmniXError>(())
+        };
+        let (first, second, third) = tokio::join!(
+            manager.handle_operation("api", trial, &metrics),
+            manager.handle_operation("api", trial, &metrics),
+            async {
+                let rejected = manager.handle_operation("api", || async { Ok(()) }, &metrics).await;
+                assert_eq!(manager.circuit("api").unwrap().state, CircuitState::HalfOpen);
+                gate.add_permits(2);
+                rejected
+            },
+        );
+
+        assert!(first.is_ok() && second.is_ok());
+        assert!(matches!(third, Err(OmniXError::CircuitBreakerActivated { .. })), "Only two trials may run at once");
+        assert_eq!(manager.circuit("api").unwrap().state, CircuitState::Closed, "Two trial successes close the circuit");
     }
 
-    #[test]
-    fn test_jwt_expiration() {
-        assert_eq!(JWT_EXPIRATION, 3600);
+    #[tokio::test]
+    async fn test_half_open_needs_consecutive_successes() {
+        let clock = Arc::new(ManualClock::new());
+        let manager = OmniXErrorManager::with_clock(OmniXErrorManagerConfig {
+            max_retries: 1,
+            circuit_breaker_threshold: 1,
+            half_open_success_threshold: 2,
+            ..fast_retry_config()
+        }, clock.clone());
+        let metrics = OmniXMetry::detached();
+
+        let _ = fail_network(&manager, "api", &metrics).await;
+        clock.advance(Duration::from_secs(60));
+        manager.handle_operation("api", || async { Ok(()) }, &metrics).await.unwrap();
+        assert_eq!(manager.circuit("api").unwrap().state, CircuitState::HalfOpen, "One success is not enough");
+
+        let result = fail_network(&manager, "api", &metrics).await;
+        assert!(matches!(result, Err(OmniXError::CircuitBreakerActivated { .. })));
+        assert!(matches!(manager.circuit("api").unwrap().state, CircuitState::Open(_)), "A trial failure re-opens");
     }
 
-    #[test]
-    fn test_rate_limit_window() {
-        assert_eq!(RATE_LIMIT_WINDOW, 60);
+    #[tokio::test]
+    async fn test_manual_clock_drives_backoff_without_waiting() {
+        let clock = Arc::new(ManualClock::new());
+        let manager = OmniXErrorManager::with_clock(OmniXErrorManagerConfig {
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(60),
+            backoff: BackoffStrategy::Exponential,
+            ..OmniXErrorManagerConfig::default()
+        }, clock.clone());
+        let metrics = OmniXMetry::detached();
+
+        let started = Instant::now();
+        let result = fail_network(&manager, "slow.dependency", &metrics).await;
+        assert!(matches!(result, Err(OmniXError::RetryLimitExceeded { retries: 3, .. })));
+        assert_eq!(clock.elapsed(), Duration::from_secs(30), "Backoff of 10s then 20s should pass on the manual clock");
+        assert!(started.elapsed() < Duration::from_secs(1));
     }
 
-    #[test]
-    fn test_rate_limit_max_requests() {
-        assert_eq!(RATE_LIMIT_MAX_REQUESTS, 100);
+    #[tokio::test]
+    async fn test_manual_clock_drives_attempt_timeouts() {
+        let clock = Arc::new(ManualClock::new());
+        let manager = OmniXErrorManager::with_clock(OmniXErrorManagerConfig {
+            timeout: Duration::from_secs(30),
+            ..OmniXErrorManagerConfig::default()
+        }, clock.clone());
+        let metrics = OmniXMetry::detached();
+
+        let started = Instant::now();
+        let result = manager
+            .handle_operation("hung.dependency", std::future::pending::<Result<(), OmniXError>>, &metrics)
+            .await;
+        assert!(matches!(result, Err(OmniXError::OperationTimeout { duration }) if duration == Duration::from_secs(30)));
+        assert_eq!(clock.elapsed(), Duration::from_secs(30), "The attempt timeout should run on the manual clock");
+        assert!(started.elapsed() < Duration::from_secs(1));
     }
 
-    #[test]
-    fn test_enable_experimental_features() {
-        assert_eq!(ENABLE_EXPERIMENTAL_FEATURES, false);
+    #[tokio::test]
+    async fn test_reopen_escalates_open_duration() {
+        let clock = Arc::new(ManualClock::new());
+        let manager = OmniXErrorManager::with_clock(OmniXErrorManagerConfig {
+            max_retries: 1,
+            circuit_breaker_threshold: 1,
+            circuit_breaker_duration: Duration::from_secs(60),
+            open_duration_multiplier: 2.0,
+            max_circuit_breaker_duration: Duration::from_secs(200),
+            ..fast_retry_config()
+        }, clock.clone());
+        let metrics = OmniXMetry::detached();
+        let ok = || async { Ok::<(), OmniXError>(()) };
+
+        let _ = fail_network(&manager, "api", &metrics).await;
+        assert_eq!(manager.circuit("api").unwrap().open_duration, Duration::from_secs(60));
+
+        clock.advance(Duration::from_secs(60));
+        let result = fail_network(&manager, "api", &metrics).await;
+        assert!(matches!(result, Err(OmniXError::CircuitBreakerActivated { duration, .. }) if duration == Duration::from_secs(120)));
+
+        clock.advance(Duration::from_secs(60));
+        assert!(manager.handle_operation("api", ok, &metrics).await.is_err(), "Still inside the escalated window");
+        clock.advance(Duration::from_secs(60));
+        let _ = fail_network(&manager, "api", &metrics).await;
+        let snapshot = manager.circuit("api").unwrap();
+        assert_eq!((snapshot.reopen_count, snapshot.open_duration), (2, Duration::from_secs(200)), "Capped at the maximum");
+
+        clock.advance(Duration::from_secs(200));
+        manager.handle_operation("api", ok, &metrics).await.unwrap();
+        let snapshot = manager.circuit("api").unwrap();
+        assert_eq!(snapshot.state, CircuitState::Closed);
+        assert_eq!((snapshot.reopen_count, snapshot.open_duration), (0, Duration::from_secs(60)));
     }
 
-    #[test]
-    fn test_use_legacy_auth() {
-        assert_eq!(USE_LEGACY_AUTH, false);
+    #[tokio::test]
+    async fn test_circuit_events_are_published() {
+        let clock = Arc::new(ManualClock::new());
+        let manager = OmniXErrorManager::with_clock(OmniXErrorManagerConfig {
+            max_retries: 2,
+            circuit_breaker_threshold: 2,
+            ..fast_retry_config()
+        }, clock.clone());
+        let metrics = OmniXMetry::detached();
+        let mut events = manager.subscribe();
+        let opened = Arc::new(AtomicUsize::new(0));
+        let counter = opened.clone();
+        manager.on_event(move |event| {
+            if matches!(event, CircuitEvent::Opened { .. }) {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        let _ = fail_network(&manager, "git.push", &metrics).await;
+        let _ = manager.handle_operation("git.push", || async { Ok(()) }, &metrics).await;
+        clock.advance(Duration::from_secs(60));
+        manager.handle_operation("git.push", || async { Ok(()) }, &metrics).await.unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            received.push(event);
+        }
+        assert!(matches!(&received[0], CircuitEvent::RetryScheduled { attempt: 2, code: "XYN-E0011", .. }));
+        assert!(matches!(&received[1], CircuitEvent::Opened { failures: 2, open_for, .. } if *open_for == Duration::from_secs(60)));
+        assert_eq!(received[2], CircuitEvent::CallRejected { key: "git.push".to_string() });
+        assert_eq!(received[3], CircuitEvent::HalfOpened { key: "git.push".to_string() });
+        assert!(matches!(&received[4], CircuitEvent::Succeeded { attempt: 1, .. }));
+        assert_eq!(received[5], CircuitEvent::Closed { key: "git.push".to_string() });
+        assert_eq!(received.len(), 6);
+        assert!(received.iter().all(|event| event.key() == "git.push"));
+        assert_eq!(opened.load(Ordering::SeqCst), 1);
     }
 
-    #[test]
-    fn test_prometheus_listener_default() {
-        env::remove_var("PROMETHEUS_LISTENER");
-        assert_eq!(&*PROMETHEUS_LISTENER, "0.0.0.0:9001");
+    fn bulkhead_config(queue_timeout: Duration) -> OmniXErrorManagerConfig {
+        OmniXErrorManagerConfig {
+            bulkhead: Some(BulkheadConfig { max_concurrent: 1, max_queued: 1, queue_timeout }),
+            ..fast_retry_config()
+        }
     }
 
-    #[test]
-    fn test_initial_log_level_default() {
-        env::remove_var("INITIAL_LOG_LEVEL");
-        assert_eq!(*INITIAL_LOG_LEVEL, tracing::Level::INFO);
+    #[tokio::test]
+    async fn test_bulkhead_queues_then_rejects() {
+        let manager = OmniXErrorManager::new(bulkhead_config(Duration::from_secs(5)));
+        let metrics = OmniXMetry::detached();
+        let gate = &tokio::sync::Semaphore::new(0);
+        let held = move || async move {
+            let _permit = gate.acquire().await.unwrap();
+            Ok::<(), OmniXError>(())
+        };
+
+        let (running, queued, rejected) = tokio::join!(
+            manager.handle_operation("db", held, &metrics),
+            manager.handle_operation("db", held, &metrics),
+            async {
+                let rejected = manager.handle_operation("db", || async { Ok(()) }, &metrics).await;
+                gate.add_permits(2);
+                rejected
+            },
+        );
+
+        assert!(running.is_ok() && queued.is_ok(), "The queued call should run once the first finishes");
+        let error = rejected.unwrap_err();
+        assert!(matches!(&error, OmniXError::BulkheadRejected { max_concurrent: 1, max_queued: 1, .. }));
+        assert_eq!(error.code(), "XYN-E0016");
+        assert_eq!(error.category(), ErrorCategory::Resilience);
+        assert_eq!(manager.circuit("db").unwrap().state, CircuitState::Closed, "Rejections do not count against the circuit");
     }
 
-    #[test]
-    fn test_log_file_path_default() {
-        env::remove_var("LOG_FILE_PATH");
-        assert_eq!(&*LOG_FILE_PATH, "xynpro.log");
+    #[tokio::test]
+    async fn test_bulkhead_queue_timeout() {
+        // The queued caller's timer is the only sleep, so a ManualClock makes it fire without waiting
+        let clock = Arc::new(ManualClock::new());
+        let manager = OmniXErrorManager::with_clock(bulkhead_config(Duration::from_secs(20)), clock.clone());
+        let metrics = OmniXMetry::detached();
+        let gate = &tokio::sync::Semaphore::new(0);
+
+        let (running, timed_out) = tokio::join!(
+            manager.handle_operation("db", move || async move {
+                let _permit = gate.acquire().await.unwrap();
+                Ok::<(), OmniXError>(())
+            }, &metrics),
+            async {
+                let result = manager.handle_operation("db", || async { Ok(()) }, &metrics).await;
+                gate.add_permits(1);
+                result
+            },
+        );
+
+        assert!(running.is_ok());
+        assert!(matches!(timed_out, Err(OmniXError::BulkheadRejected { .. })));
+        assert_eq!(clock.elapsed(), Duration::from_secs(20));
+        assert!(manager.handle_operation("other", || async { Ok(()) }, &metrics).await.is_ok(), "Bulkheads are per key");
     }
 
-    #[test]
-    fn test_git_remote_default() {
-        env::remove_var("GIT_REMOTE");
-        assert_eq!(&*GIT_REMOTE, "origin");
+    #[tokio::test]
+    async fn test_bulkhead_slot_is_released_during_backoff() {
+        let manager = OmniXErrorManager::new(OmniXErrorManagerConfig {
+            backoff: BackoffStrategy::Fixed,
+            base_delay: Duration::from_millis(50),
+            bulkhead: Some(BulkheadConfig { max_concurrent: 1, max_queued: 0, queue_timeout: Duration::ZERO }),
+            ..fast_retry_config()
+        });
+        let metrics = OmniXMetry::detached();
+        let first_failure = &tokio::sync::Notify::new();
+        let attempts = &AtomicUsize::new(0);
+
+        let (retrying, admitted) = tokio::join!(
+            manager.handle_operation("db", move || async move {
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    first_failure.notify_one();
+                    return Err(OmniXError::NetworkError("reset".to_string()));
+                }
+                Ok(())
+            }, &metrics),
+            async {
+                first_failure.notified().await;
+                manager.handle_operation("db", || async { Ok(()) }, &metrics).await
+            },
+        );
+
+        assert!(retrying.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(admitted.is_ok(), "A caller in backoff holds no slot: {:?}", admitted);
     }
 
-    #[test]
-    fn test_git_branch_default() {
-        env::remove_var("GIT_BRANCH");
-        assert_eq!(&*GIT_BRANCH, "main");
+    #[tokio::test]
+    async fn test_hedged_attempt_takes_the_first_success() {
+        let manager = OmniXErrorManager::new(fast_retry_config());
+        let metrics = OmniXMetry::detached();
+        let calls = &AtomicUsize::new(0);
+        let slow_primary = move || async move {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                return Ok("primary");
+            }
+            Ok("hedge")
+        };
+
+        let started = Instant::now();
+        let result = manager.handle_error_hedged(Duration::from_millis(20), slow_primary, &metrics).await;
+        assert_eq!(result.unwrap(), "hedge");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        calls.store(1, Ordering::SeqCst);
+        let result = manager.handle_error_hedged(Duration::from_millis(20), slow_primary, &metrics).await;
+        assert_eq!(result.unwrap(), "hedge");
+        assert_eq!(calls.load(Ordering::SeqCst), 2, "A fast primary is never hedged");
     }
 
-    #[test]
-    fn test_git_commit_message_default() {
-        env::remove_var("GIT_COMMIT_MESSAGE");
-        assert_eq!(&*GIT_COMMIT_MESSAGE, "Automated update via xyngit");
+    #[tokio::test]
+    async fn test_hedge_is_skipped_when_the_bulkhead_is_full() {
+        let manager = OmniXErrorManager::new(bulkhead_config(Duration::ZERO));
+        let metrics = OmniXMetry::detached();
+        let calls = &AtomicUsize::new(0);
+
+        let result = manager.handle_operation_hedged("db", Duration::from_millis(10), move || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok("primary")
+        }, &metrics).await;
+        assert_eq!(result.unwrap(), "primary");
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "The only slot belongs to the primary");
     }
 
-    #[test]
-    fn test_circuit_breaker_threshold_default() {
-        env::remove_var("CIRCUIT_BREAKER_THRESHOLD");
-        assert_eq!(*CIRCUIT_BREAKER_THRESHOLD, 10);
+    #[tokio::test]
+    async fn test_hedged_attempt_fails_only_when_both_copies_fail() {
+        let manager = OmniXErrorManager::new(OmniXErrorManagerConfig { max_retries: 1, ..fast_retry_config() });
+        let metrics = OmniXMetry::detached();
+        let calls = &AtomicUsize::new(0);
+
+        let result = manager.handle_error_hedged(Duration::from_millis(10), move || async move {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(if call == 0 { 50 } else { 0 })).await;
+            if call == 0 {
+                Ok(())
+            } else {
+                Err(OmniXError::NetworkError("hedge lost".to_string()))
+            }
+        }, &metrics).await;
+        assert!(result.is_ok(), "The primary still wins after the hedge fails");
+
+        let result: Result<(), OmniXError> = manager.handle_error_hedged(Duration::from_millis(10), || async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            Err(OmniXError::NetworkError("unreachable".to_string()))
+        }, &metrics).await;
+        assert!(matches!(result, Err(OmniXError::RetryLimitExceeded { retries: 1, .. })));
     }
 
-    #[test]
-    fn test_circuit_breaker_duration_default() {
-        env::remove_var("CIRCUIT_BREAKER_DURATION");
-        assert_eq!(*CIRCUIT_BREAKER_DURATION, std::time::Duration::from_secs(60));
+    #[tokio::test]
+    async fn test_fallback_runs_when_retries_are_exhausted_or_the_circuit_is_open() {
+        let manager = OmniXErrorManager::new(OmniXErrorManagerConfig { circuit_breaker_threshold: 3, ..fast_retry_config() });
+        let metrics = OmniXMetry::detached();
+        let failing = || async { Err::<&str, _>(OmniXError::NetworkError("unreachable".to_string())) };
+
+        let tripped = manager
+            .handle_operation_with_fallback("cache", failing, |e| async move {
+                assert!(matches!(e, OmniXError::CircuitBreakerActivated { .. }));
+                Ok("stale")
+            }, &metrics)
+            .await;
+        assert_eq!(tripped.unwrap(), "stale");
+
+        let rejected = manager.handle_operation_with_fallback("cache", failing, |_| async { Ok("stale") }, &metrics).await;
+        assert_eq!(rejected.unwrap(), "stale", "Calls rejected by the open circuit fall back too");
+
+        let manager = OmniXErrorManager::new(fast_retry_config());
+        let exhausted = manager
+            .handle_error_with_fallback(failing, |e| async move {
+                Err::<&str, _>(OmniXError::caused_by(OmniXError::ConfigError("no fallback".to_string()), e))
+            }, &metrics)
+            .await;
+        assert!(matches!(exhausted.unwrap_err().root(), OmniXError::ConfigError(_)));
+
+        let permanent = manager
+            .handle_error_with_fallback(|| async { Err::<&str, _>(OmniXError::ValidationError("bad".to_string())) }, |_| async {
+                Ok("unreachable")
+            }, &metrics)
+            .await;
+        assert!(matches!(permanent, Err(OmniXError::ValidationError(_))), "Permanent errors skip the fallback");
     }
 
-    #[test]
-    fn test_base_delay_default() {
-        env::remove_var("BASE_DELAY");
-        assert_eq!(*BASE_DELAY, std::time::Duration::from_millis(100));
+    #[tokio::test]
+    async fn test_fallback_runs_on_deadline_and_bulkhead_rejection() {
+        let clock = Arc::new(ManualClock::new());
+        let manager = OmniXErrorManager::with_clock(OmniXErrorManagerConfig {
+            max_retries: 10,
+            backoff: BackoffStrategy::Fixed,
+            base_delay: Duration::from_millis(400),
+            overall_timeout: Some(Duration::from_secs(1)),
+            ..OmniXErrorManagerConfig::default()
+        }, clock);
+        let metrics = OmniXMetry::detached();
+        let deadline = manager
+            .handle_operation_with_fallback("search", || async { Err::<&str, _>(OmniXError::NetworkError("slow".to_string())) }, |e| async move {
+                assert!(matches!(e, OmniXError::DeadlineExceeded { .. }));
+                Ok("cached")
+            }, &metrics)
+            .await;
+        assert_eq!(deadline.unwrap(), "cached");
+
+        let manager = OmniXErrorManager::new(bulkhead_config(Duration::from_secs(5)));
+        let gate = &tokio::sync::Semaphore::new(0);
+        let held = move || async move {
+            let _permit = gate.acquire().await.unwrap();
+            Ok::<&str, OmniXError>("fresh")
+        };
+        let (running, queued, rejected) = tokio::join!(
+            manager.handle_operation("db", held, &metrics),
+            manager.handle_operation("db", held, &metrics),
+            async {
+                let rejected = manager
+                    .handle_operation_with_fallback("db", || async { Ok("fresh") }, |e| async move {
+                        assert!(matches!(e, OmniXError::BulkheadRejected { .. }));
+                        Ok("shed")
+                    }, &metrics)
+                    .await;
+                gate.add_permits(2);
+                rejected
+            },
+        );
+        assert!(running.is_ok() && queued.is_ok());
+        assert_eq!(rejected.unwrap(), "shed");
     }
 
     #[test]
-    fn test_max_delay_default() {
-        env::remove_var("MAX_DELAY");
-        assert_eq!(*MAX_DELAY, std::time::Duration::from_secs(10));
+    fn test_fallback_eligibility_looks_through_wrappers() {
+        let timeout = OmniXError::OperationTimeout { duration: Duration::from_secs(1) };
+        assert!(timeout.is_fallback_eligible());
+        assert!(OmniXError::RateLimited { key: "api".to_string(), retry_after: Duration::from_secs(1) }.is_fallback_eligible());
+
+        let wrapped = OmniXError::caused_by(timeout, std::io::Error::from(std::io::ErrorKind::TimedOut)).context("refreshing cache");
+        assert!(wrapped.is_fallback_eligible(), "Context and Caused wrappers are looked through");
+        assert!(!OmniXError::ValidationError("bad".to_string()).context("parsing").is_fallback_eligible());
     }
 
-    #[test]
-    fn test_default_timeout_default() {
-        env::remove_var("DEFAULT_TIMEOUT");
-        assert_eq!(*DEFAULT_TIMEOUT, std::time::Duration::from_secs(30));
+    fn persisted_config(dir: &tempfile::TempDir) -> OmniXErrorManagerConfig {
+        OmniXErrorManagerConfig {
+            circuit_breaker_threshold: 3,
+            state_file: Some(dir.path().join("state").join("circuits.json")),
+            ..fast_retry_config()
+        }
     }
 
-    #[test]
-    fn test_max_retries_default() {
-        env::remove_var("MAX_RETRIES");
-        assert_eq!(get_max_retries(), 3);
+    #[tokio::test]
+    async fn test_open_circuit_survives_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let metrics = OmniXMetry::detached();
+        let wall_start = chrono::Local::now();
+
+        let first_run = OmniXErrorManager::with_clock(persisted_config(&dir), Arc::new(ManualClock::at(wall_start)));
+        let result = fail_network(&first_run, "git.push", &metrics).await;
+        assert!(matches!(result, Err(OmniXError::CircuitBreakerActivated { count: 3, .. })));
+        drop(first_run);
+
+        let calls = &AtomicUsize::new(0);
+        let counted = move || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        };
+        let clock = Arc::new(ManualClock::at(wall_start + chrono::Duration::seconds(30)));
+        let second_run = OmniXErrorManager::with_clock(persisted_config(&dir), clock.clone());
+        let rejected = second_run.handle_operation("git.push", counted, &metrics).await;
+        assert!(matches!(rejected, Err(OmniXError::CircuitBreakerActivated { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 0, "The open circuit from the previous run is respected");
+        assert!(second_run.handle_operation("git.pull", counted, &metrics).await.is_ok(), "Other keys are unaffected");
+
+        clock.advance(Duration::from_secs(31));
+        assert!(second_run.handle_operation("git.push", counted, &metrics).await.is_ok(), "The open period carries over too");
+        let third_run = OmniXErrorManager::new(persisted_config(&dir));
+        assert!(third_run.handle_operation("git.push", counted, &metrics).await.is_ok());
+        assert_eq!(third_run.circuit("git.push").unwrap().state, CircuitState::Closed);
     }
-}
-"#, project_name)
-}
 
-fn generate_omnixerror_tests_content(project_name: &str) -> String {
-    format!(r#"// tests/omnixerror_tests.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[TESTS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+    #[tokio::test]
+    async fn test_failure_counts_accumulate_across_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let metrics = OmniXMetry::detached();
+        let config = OmniXErrorManagerConfig { max_retries: 1, ..persisted_config(&dir) };
 
-#[cfg(test)]
-mod tests {
-    use test6::omnixtracker::OmniXError;
-    use std::time::Duration;
+        for _ in 0..2 {
+            let run = OmniXErrorManager::new(config.clone());
+            assert!(matches!(fail_network(&run, "db", &metrics).await, Err(OmniXError::RetryLimitExceeded { .. })));
+        }
+        let run = OmniXErrorManager::new(config.clone());
+        assert!(matches!(fail_network(&run, "db", &metrics).await, Err(OmniXError::CircuitBreakerActivated { count: 3, .. })));
 
-    #[test]
-    fn test_operation_failed_error() {
-        let error = OmniXError::OperationFailed { 
-            operation: "test".to_string(),
-            details: "Some error occurred".to_string() 
-        };
-        assert_eq!(format!("{}", error), "Operation failed during test: Some error occurred");
+        assert!(run.reset_circuit("db"));
+        let run = OmniXErrorManager::new(config);
+        assert!(matches!(fail_network(&run, "db", &metrics).await, Err(OmniXError::RetryLimitExceeded { .. })), "Resets clear the file too");
     }
 
-    #[test]
-    fn test_retry_limit_exceeded_error() {
-        let error = OmniXError::RetryLimitExceeded {
-            retries: 5,
-            last_error: "Last attempt failed".to_string(),
-        };
-        assert_eq!(format!("{}", error), "Retry limit exceeded after 5 attempts: Last attempt failed");
-    }
+    #[tokio::test]
+    async fn test_healthy_calls_do_not_touch_the_state_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let metrics = OmniXMetry::detached();
+        let config = persisted_config(&dir);
+        let state_file = config.state_file.clone().unwrap();
 
-    #[test]
-    fn test_circuit_breaker_activated_error() {
-        let error = OmniXError::CircuitBreakerActivated {
-            count: 10,
-            duration: Duration::from_secs(60),
-        };
-        assert_eq!(format!("{}", error), "Circuit breaker activated after 10 errors in 60s");
+        let manager = OmniXErrorManager::new(config);
+        for _ in 0..3 {
+            assert!(manager.handle_operation("db", || async { Ok(()) }, &metrics).await.is_ok());
+        }
+        assert!(!state_file.exists(), "Nothing worth recording happened");
+
+        let _ = fail_network(&manager, "db", &metrics).await;
+        assert!(state_file.exists());
     }
 
     #[test]
-    fn test_operation_timeout_error() {
-        let error = OmniXError::OperationTimeout {
-            duration: Duration::from_secs(30),
+    fn test_default_state_file_prefers_xdg_state_home() {
+        let state_file = |xdg: Option<&str>, home: Option<&str>| {
+            OmniXErrorManagerConfig::default_state_file_from(xdg.map(Into::into), home.map(Into::into))
         };
-        assert_eq!(format!("{}", error), "Operation timed out after 30s");
-    }
 
-    #[test]
-    fn test_file_system_error() {
-        let error = OmniXError::FileSystemError("Disk not found".to_string());
-        assert_eq!(format!("{}", error), "File system error: Disk not found");
+        let path = state_file(Some("/var/tmp/state"), Some("/home/xyn"));
+        assert!(path.starts_with("/var/tmp/state"));
+        assert!(path.ends_with("circuits.json"));
+        assert!(state_file(Some(""), Some("/home/xyn")).starts_with("/home/xyn/.local/state"), "An empty XDG_STATE_HOME is unset");
+        assert_eq!(state_file(None, None), std::path::Path::new("Xdocs").join("circuits.json"));
     }
 
     #[test]
-    fn test_env_var_error() {
-        let error = OmniXError::EnvVarError("Missing environment variable".to_string());
-        assert_eq!(format!("{}", error), "Environment variable error: Missing environment variable");
+    fn test_blocking_calls_share_state_with_async_callers() {
+        let manager = OmniXErrorManager::new(OmniXErrorManagerConfig { circuit_breaker_threshold: 2, ..fast_retry_config() });
+        let metrics = OmniXMetry::detached();
+
+        let mut attempts = 0;
+        let result = manager.handle_error_blocking(|| {
+            attempts += 1;
+            if attempts < 2 {
+                Err(OmniXError::NetworkError("unreachable".to_string()))
+            } else {
+                Ok(attempts)
+            }
+        }, &metrics);
+        assert_eq!(result.unwrap(), 2);
+
+        let tripped: Result<(), OmniXError> = manager.handle_operation_blocking("git.pull", || {
+            Err(OmniXError::NetworkError("unreachable".to_string()))
+        }, &metrics);
+        assert!(matches!(tripped, Err(OmniXError::CircuitBreakerActivated { count: 2, .. })));
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let rejected = manager.handle_operation("git.pull", || async { Ok(()) }, &metrics).await;
+            assert!(matches!(rejected, Err(OmniXError::CircuitBreakerActivated { .. })), "Async callers see the same circuit");
+
+            let nested = manager.handle_error_blocking(|| Ok(7), &metrics);
+            assert_eq!(nested.unwrap(), 7, "A multi-threaded runtime hands the worker off with block_in_place");
+        });
+
+        let current_thread = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        current_thread.block_on(async {
+            let error = manager.handle_error_blocking(|| Ok(()), &metrics).unwrap_err();
+            assert!(matches!(error, OmniXError::ConfigError(_)), "Blocking a current-thread runtime is refused");
+            assert_eq!(error.retry_class(), RetryClass::Permanent, "Retrying a misuse cannot help");
+        });
     }
 
-    #[test]
-    fn test_project_creation_error() {
-        let error = OmniXError::ProjectCreationError("Unable to create project".to_string());
-        assert_eq!(format!("{}", error), "Project creation error: Unable to create project");
-    }
+    #[tokio::test]
+    async fn test_attempt_timeouts_retry_when_configured() {
+        let config = OmniXErrorManagerConfig { timeout: Duration::from_millis(20), ..fast_retry_config() };
+        let metrics = OmniXMetry::detached();
+        let attempts = &AtomicUsize::new(0);
+        let slow_then_fast = move || async move {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+            Ok::<(), OmniXError>(())
+        };
 
-    #[test]
-    fn test_metrics_init_error() {
-        let error = OmniXError::MetricsInitError("Failed to initialize metrics".to_string());
-        assert_eq!(format!("{}", error), "Metrics initialization error: Failed to initialize metrics");
-    }
+        let manager = OmniXErrorManager::new(config.clone());
+        let result = manager.handle_error(slow_then_fast, &metrics).await;
+        assert!(matches!(result, Err(OmniXError::OperationTimeout { .. })), "Timeouts are final by default");
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert_eq!(manager.circuit("default").unwrap().error_count, 1, "A timeout that is not retried still counts as a failure");
 
-    #[test]
-    fn test_logging_error() {
-        let error = OmniXError::LoggingError("Log file not found".to_string());
-        assert_eq!(format!("{}", error), "Logging error: Log file not found");
-    }
+        attempts.store(0, Ordering::SeqCst);
+        let manager = OmniXErrorManager::new(OmniXErrorManagerConfig { retry_on_timeout: true, ..config });
+        assert!(manager.handle_error(slow_then_fast, &metrics).await.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
 
-    #[test]
-    fn test_database_error() {
-        let error = OmniXError::DatabaseError("Connection failed".to_string());
-        assert_eq!(format!("{}", error), "Database error: Connection failed");
+        let result = manager.handle_operation("hung", std::future::pending::<Result<(), OmniXError>>, &metrics).await;
+        assert!(matches!(result, Err(OmniXError::RetryLimitExceeded { retries: 3, .. })));
+        assert_eq!(manager.circuit("hung").unwrap().error_count, 3, "Retried timeouts count as failures");
     }
 
-    #[test]
-    fn test_network_error() {
-        let error = OmniXError::NetworkError("Network unreachable".to_string());
-        assert_eq!(format!("{}", error), "Network error: Network unreachable");
+    #[tokio::test]
+    async fn test_overall_deadline_bounds_the_retry_sequence() {
+        let clock = Arc::new(ManualClock::new());
+        let manager = OmniXErrorManager::with_clock(OmniXErrorManagerConfig {
+            max_retries: 10,
+            backoff: BackoffStrategy::Fixed,
+            base_delay: Duration::from_millis(400),
+            overall_timeout: Some(Duration::from_secs(1)),
+            ..OmniXErrorManagerConfig::default()
+        }, clock.clone());
+        let metrics = OmniXMetry::detached();
+
+        let result: Result<(), OmniXError> = manager
+            .handle_error(|| async { Err(OmniXError::NetworkError("unreachable".to_string())) }, &metrics)
+            .await;
+        let error = result.unwrap_err();
+        assert!(matches!(error, OmniXError::DeadlineExceeded { attempts: 3, .. }), "Got {:?}", error);
+        assert_eq!(error.code(), "XYN-E0018");
+        assert_eq!(error.retry_class(), RetryClass::Permanent);
+        assert_eq!(clock.elapsed(), Duration::from_millis(800), "The third backoff would overrun the deadline");
+
+        let hung = manager.handle_operation("hung", std::future::pending::<Result<(), OmniXError>>, &metrics).await;
+        assert!(matches!(hung, Err(OmniXError::DeadlineExceeded { attempts: 1, .. })), "Got {:?}", hung);
+        assert_eq!(manager.circuit("hung").unwrap().error_count, 1, "Hanging until the deadline counts against the circuit");
     }
 
-    #[test]
-    fn test_authentication_error() {
-        let error = OmniXError::AuthenticationError("Invalid credentials".to_string());
-        assert_eq!(format!("{}", error), "Authentication error: Invalid credentials");
+    #[tokio::test]
+    async fn test_cancellation_aborts_the_retry_sequence() {
+        let manager = OmniXErrorManager::new(OmniXErrorManagerConfig {
+            backoff: BackoffStrategy::Fixed,
+            base_delay: Duration::from_secs(30),
+            ..OmniXErrorManagerConfig::default()
+        });
+        let metrics = OmniXMetry::detached();
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+
+        let started = Instant::now();
+        let result: Result<(), OmniXError> = manager
+            .handle_error_cancellable(|| async { Err(OmniXError::NetworkError("unreachable".to_string())) }, &metrics, &cancel)
+            .await;
+        assert!(matches!(result, Err(OmniXError::Cancelled { attempts: 1, .. })));
+        assert!(started.elapsed() < Duration::from_secs(5), "The backoff sleep should be abandoned");
+
+        let result = manager.handle_error_cancellable(|| async { Ok(()) }, &metrics, &cancel).await;
+        assert!(matches!(result, Err(OmniXError::Cancelled { attempts: 0, .. })), "An already cancelled token admits nothing");
     }
 
     #[test]
-    fn test_authorization_error() {
-        let error = OmniXError::AuthorizationError("Access denied".to_string());
-        assert_eq!(format!("{}", error), "Authorization error: Access denied");
+    fn test_backoff_strategies() {
+        let base = Duration::from_millis(100);
+        let max = Duration::from_secs(1);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        assert_eq!(BackoffStrategy::Fixed.delay(3, base, base, max, &mut rng), base);
+        assert_eq!(BackoffStrategy::Linear.delay(2, base, base, max, &mut rng), Duration::from_millis(300));
+        assert_eq!(BackoffStrategy::Exponential.delay(2, base, base, max, &mut rng), Duration::from_millis(400));
+        assert_eq!(BackoffStrategy::Exponential.delay(10, base, base, max, &mut rng), max, "Delays are capped");
+        assert_eq!(BackoffStrategy::Exponential.delay(usize::MAX, base, base, max, &mut rng), max);
+
+        for retries in 0..20 {
+            let full = BackoffStrategy::FullJitter.delay(retries, base, base, max, &mut rng);
+            assert!(full <= max);
+            let decorrelated = BackoffStrategy::DecorrelatedJitter.delay(retries, Duration::from_millis(200), base, max, &mut rng);
+            assert!(decorrelated >= base && decorrelated <= Duration::from_millis(600));
+        }
+
+        let custom = BackoffStrategy::Custom(std::sync::Arc::new(|retries, previous| previous + Duration::from_millis(retries as u64)));
+        assert_eq!(custom.delay(5, base, base, max, &mut rng), Duration::from_millis(105));
     }
 
     #[test]
-    fn test_validation_error() {
-        let error = OmniXError::ValidationError("Input is invalid".to_string());
-        assert_eq!(format!("{}", error), "Validation error: Input is invalid");
+    fn test_jitter_is_deterministic_with_a_seed() {
+        let sample = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..5)
+                .map(|retries| BackoffStrategy::DecorrelatedJitter.delay(retries, Duration::from_millis(50), Duration::from_millis(10), Duration::from_secs(5), &mut rng))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sample(42), sample(42));
+        assert_ne!(sample(42), sample(43));
     }
 }
 "#, project_name)
 }
 
 fn generate_omnixmetry_tests_content(project_name: &str) -> String {
-    format!(r#"// tests/omnixmetry_tests.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[TESTS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+    render_crate_template(r#"// tests/omnixmetry_tests.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXMETRY-TESTS]Xyn>=====S===t===u===d===i===o===s======[R|$>
 
-use test6::omnixtracker::OmniXMetry;
+use test6::omnixtracker::{LabelRegistry, ManualClock, OmniXMetry, OVERFLOW_LABEL_VALUE};
 use test6::constants::{INITIAL_LOG_LEVEL, LOG_FILE_PATH};
+use chrono::{Local, TimeZone};
 use std::env;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing::Level;
 use std::sync::Once;
 
@@ -1771,17 +6308,382 @@ fn test_rotate_log_file() {
         println!("Failed to initialize OmniXMetry. Skipping this test.");
     }
 }
+
+#[test]
+fn test_rotated_log_names_follow_clock() {
+    let clock = Arc::new(ManualClock::at(Local.with_ymd_and_hms(2024, 3, 5, 14, 7, 0).unwrap()));
+    let omnixmetry = OmniXMetry::detached().with_clock(clock.clone());
+    let xdocs = tempfile::tempdir().unwrap();
+
+    let first = omnixmetry.generate_new_log_path(xdocs.path()).unwrap();
+    assert_eq!(first.file_name().unwrap(), "03-05-2024_14-07_001.log");
+    std::fs::write(&first, "").unwrap();
+
+    let second = omnixmetry.generate_new_log_path(xdocs.path()).unwrap();
+    assert_eq!(second.file_name().unwrap(), "03-05-2024_14-07_002.log", "Same minute should bump the counter");
+
+    clock.advance(Duration::from_secs(60));
+    let next_minute = omnixmetry.generate_new_log_path(xdocs.path()).unwrap();
+    assert_eq!(next_minute.file_name().unwrap(), "03-05-2024_14-08_001.log");
+}
+
+#[test]
+fn test_unregistered_label_keys_are_dropped() {
+    let registry = LabelRegistry::with_defaults();
+    let resolved = registry.resolve("error_manager.failures_by_code", &[("code", "XYN-E0011"), ("message", "connection refused")]);
+    assert_eq!(resolved, vec![("code".to_string(), "XYN-E0011".to_string())]);
+    assert!(!registry.is_allowed("message"));
+}
+
+#[test]
+fn test_excess_label_values_are_bucketed_per_metric() {
+    let registry = LabelRegistry::new();
+    registry.allow("tenant", 2);
+    let tenant = |metric: &str, value: &str| registry.resolve(metric, &[("tenant", value)])[0].1.clone();
+
+    assert_eq!(tenant("requests", "a"), "a");
+    assert_eq!(tenant("requests", "b"), "b");
+    assert_eq!(tenant("requests", "c"), OVERFLOW_LABEL_VALUE);
+    assert_eq!(tenant("requests", "a"), "a", "Values already seen keep their own series");
+    assert_eq!(tenant("errors", "c"), "c", "Limits apply per metric");
+}
+
+#[test]
+fn test_labelled_metric_handles() {
+    let registry = Arc::new(LabelRegistry::new());
+    registry.allow("policy", 4);
+    let omnixmetry = OmniXMetry::detached().with_label_registry(registry.clone());
+
+    omnixmetry.counter("calls", &[("policy", "interactive")]).increment(1);
+    omnixmetry.gauge("in_flight", &[("policy", "interactive")]).set(2.0);
+    omnixmetry.histogram("latency_ms", &[("policy", "interactive"), ("request_id", "42")]).record(12.5);
+    assert!(Arc::ptr_eq(omnixmetry.label_registry(), &registry));
+    assert_eq!(
+        registry.resolve("latency_ms", &[("policy", "interactive"), ("request_id", "43")]),
+        vec![("policy".to_string(), "interactive".to_string())],
+        "request_id is not registered, so the handle recorded without it"
+    );
+    assert!(LabelRegistry::global().is_allowed("code"));
+}
+
+#[test]
+fn test_labeled_counter_increments_go_through_the_registry() {
+    let registry = Arc::new(LabelRegistry::new());
+    registry.allow("tenant", 1);
+    let omnixmetry = OmniXMetry::detached().with_label_registry(registry.clone());
+
+    omnixmetry.increment_labeled_counter("jobs".to_string(), 1, &[("tenant", "a"), ("job_id", "17")]);
+    assert_eq!(registry.resolve("jobs", &[("tenant", "b")])[0].1, OVERFLOW_LABEL_VALUE, "The first value took the only slot");
+    assert_eq!(registry.resolve("jobs", &[("tenant", "a")])[0].1, "a");
+}
+}
+"#, project_name)
+}
+
+fn generate_omnixlimit_tests_content(project_name: &str) -> String {
+    render_crate_template(r#"// tests/omnixlimit_tests.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXLIMIT-TESTS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+#[cfg(test)]
+mod tests {
+    use test6::omnixtracker::{
+        CircuitState, ErrorCategory, ErrorReport, ManualClock, OmniXError, OmniXErrorManager, OmniXErrorManagerConfig,
+        OmniXMetry, OmniXRateLimiter, RateDecision, RateLimitAlgorithm, RateLimitConfig, RetryClass,
+    };
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn limiter(algorithm: RateLimitAlgorithm, clock: Arc<ManualClock>) -> OmniXRateLimiter {
+        OmniXRateLimiter::with_clock(RateLimitConfig {
+            algorithm,
+            window: Duration::from_secs(3),
+            max_requests: 3,
+            max_keys: 100,
+        }, clock)
+    }
+
+    #[test]
+    fn test_token_bucket_refills_over_the_window() {
+        let clock = Arc::new(ManualClock::new());
+        let limiter = limiter(RateLimitAlgorithm::TokenBucket, clock.clone());
+
+        assert_eq!(limiter.check("alice"), RateDecision::Allowed { remaining: 2 });
+        assert_eq!(limiter.check("alice"), RateDecision::Allowed { remaining: 1 });
+        assert_eq!(limiter.check("alice"), RateDecision::Allowed { remaining: 0 });
+        assert_eq!(limiter.check("alice"), RateDecision::Denied { retry_after: Duration::from_secs(1) });
+        assert!(matches!(limiter.check("bob"), RateDecision::Allowed { .. }), "Callers have separate buckets");
+
+        clock.advance(Duration::from_secs(1));
+        assert!(matches!(limiter.check("alice"), RateDecision::Allowed { .. }), "One token refills per second");
+        assert!(matches!(limiter.check("alice"), RateDecision::Denied { .. }));
+    }
+
+    #[test]
+    fn test_sliding_window_log_counts_trailing_requests() {
+        let clock = Arc::new(ManualClock::new());
+        let limiter = limiter(RateLimitAlgorithm::SlidingWindowLog, clock.clone());
+
+        limiter.check("alice");
+        clock.advance(Duration::from_secs(1));
+        limiter.check("alice");
+        limiter.check("alice");
+        assert_eq!(limiter.check("alice"), RateDecision::Denied { retry_after: Duration::from_secs(2) });
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(limiter.check("alice"), RateDecision::Allowed { remaining: 0 }, "The first request has left the window");
+        assert!(matches!(limiter.check("alice"), RateDecision::Denied { .. }));
+    }
+
+    #[test]
+    fn test_idle_keys_are_evicted() {
+        let clock = Arc::new(ManualClock::new());
+        let limiter = OmniXRateLimiter::with_clock(RateLimitConfig {
+            max_keys: 2,
+            ..RateLimitConfig::default()
+        }, clock.clone());
+
+        limiter.check("a");
+        clock.advance(Duration::from_secs(1));
+        limiter.check("b");
+        clock.advance(Duration::from_secs(1));
+        limiter.check("c");
+        assert_eq!(limiter.tracked_keys(), 2);
+        assert!(!limiter.reset("a"), "The least recently seen key goes first");
+    }
+
+    #[test]
+    fn test_rate_limited_error() {
+        let clock = Arc::new(ManualClock::new());
+        let limiter = limiter(RateLimitAlgorithm::TokenBucket, clock);
+        let metrics = OmniXMetry::detached();
+        for _ in 0..3 {
+            limiter.acquire("alice", &metrics).unwrap();
+        }
+
+        let error = limiter.acquire("alice", &metrics).unwrap_err();
+        assert_eq!(error.code(), "XYN-E0017");
+        assert_eq!(error.category(), ErrorCategory::Resilience);
+        assert_eq!(error.retry_class(), RetryClass::RetryAfter(Duration::from_secs(1)));
+        assert_eq!(ErrorReport::from(&error).http_status(), 429);
+    }
+
+    #[tokio::test]
+    async fn test_manager_applies_rate_limit_before_the_circuit() {
+        let clock = Arc::new(ManualClock::new());
+        let limiter = Arc::new(limiter(RateLimitAlgorithm::SlidingWindowLog, clock.clone()));
+        let manager = OmniXErrorManager::with_clock(OmniXErrorManagerConfig::default(), clock.clone())
+            .with_rate_limiter(limiter);
+        let metrics = OmniXMetry::detached();
+
+        for _ in 0..3 {
+            manager.handle_operation("git.push", || async { Ok(()) }, &metrics).await.unwrap();
+        }
+        let result = manager.handle_operation("git.push", || async { Ok(()) }, &metrics).await;
+        assert!(matches!(result, Err(OmniXError::RateLimited { .. })));
+        assert_eq!(manager.circuit("git.push").unwrap().state, CircuitState::Closed);
+        assert_eq!(manager.circuit("git.push").unwrap().error_count, 0);
+    }
+}
+"#, project_name)
+}
+
+fn generate_omnixpolicy_tests_content(project_name: &str) -> String {
+    render_crate_template(r##"// tests/omnixpolicy_tests.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[OMNIXPOLICY-TESTS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+
+#[cfg(test)]
+mod tests {
+    use test6::omnixtracker::{
+        BackoffStrategy, CancellationToken, CircuitBreakerPolicy, OmniXError, OmniXErrorManager, OmniXErrorManagerConfig,
+        OmniXMetry, ResiliencePolicies, SlidingWindow, DEFAULT_POLICY, RESILIENCE_CONFIG_FILE,
+    };
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn base() -> OmniXErrorManagerConfig {
+        OmniXErrorManagerConfig {
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            ..OmniXErrorManagerConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_shipped_policies_are_valid() {
+        let policies = ResiliencePolicies::load(Path::new(RESILIENCE_CONFIG_FILE), &OmniXErrorManagerConfig::default()).unwrap();
+        assert!(policies.get("interactive").is_some());
+        let background = policies.get("background").unwrap();
+        assert!(matches!(background.circuit_breaker_policy, CircuitBreakerPolicy::FailureRate { .. }));
+        assert_eq!(background.bulkhead.unwrap().max_concurrent, 4);
+    }
+
+    #[test]
+    fn test_breaker_slow_call_settings_are_applied() {
+        let policies = ResiliencePolicies::from_toml(r#"
+            [policies.reports.breaker]
+            failure_rate = 0.5
+            window_calls = 20
+            slow_call_ms = 750
+            slow_call_rate = 0.8
+        "#, &base()).unwrap();
+
+        match policies.get("reports").unwrap().circuit_breaker_policy {
+            CircuitBreakerPolicy::FailureRate { slow_call_duration, slow_call_rate_threshold, .. } => {
+                assert_eq!(slow_call_duration, Some(Duration::from_millis(750)));
+                assert_eq!(slow_call_rate_threshold, 0.8);
+            }
+            other => panic!("expected a failure-rate policy, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_breaker_window_can_span_time() {
+        let policies = ResiliencePolicies::from_toml("[policies.hourly.breaker]\nfailure_rate = 0.5\nwindow_secs = 3600", &base()).unwrap();
+        assert!(matches!(
+            policies.get("hourly").unwrap().circuit_breaker_policy,
+            CircuitBreakerPolicy::FailureRate { window: SlidingWindow::Time(span), .. } if span == Duration::from_secs(3600)
+        ));
+    }
+
+    #[test]
+    fn test_policies_override_only_what_they_set() {
+        let policies = ResiliencePolicies::from_toml(r#"
+            [policies.reads]
+            max_retries = 5
+            backoff = "fixed"
+            timeout_ms = 250
+
+            [policies.reads.breaker]
+            threshold = 2
+        "#, &base()).unwrap();
+
+        let reads = policies.get("reads").unwrap();
+        assert_eq!(reads.max_retries, 5);
+        assert!(matches!(reads.backoff, BackoffStrategy::Fixed));
+        assert_eq!(reads.timeout, Duration::from_millis(250));
+        assert_eq!(reads.circuit_breaker_threshold, 2);
+        assert_eq!(reads.base_delay, base().base_delay, "Omitted keys keep the base value");
+        assert_eq!(policies.default_config().max_retries, base().max_retries);
+        assert_eq!(policies.names().collect::<Vec<_>>(), vec![DEFAULT_POLICY, "reads"]);
+    }
+
+    #[test]
+    fn test_invalid_policies_are_rejected() {
+        let cases = [
+            ("[policies.slow]\nbase_delay_ms = 500\nmax_delay_ms = 100", "slow"),
+            ("[policies.never]\nmax_retries = 0", "never"),
+            ("[policies.rate.breaker]\nfailure_rate = 1.5", "rate"),
+            ("[policies.pool.bulkhead]\nmax_concurrent = 0", "pool"),
+            ("[policies.window.breaker]\nwindow_calls = 20", "window"),
+            ("[policies.minimum.breaker]\nminimum_calls = 5", "minimum"),
+            ("[policies.sluggish.breaker]\nslow_call_ms = 500", "sluggish"),
+            ("[policies.crawl.breaker]\nfailure_rate = 0.5\nslow_call_ms = 500\nslow_call_rate = 0.0", "crawl"),
+            ("[policies.hourly.breaker]\nwindow_secs = 3600", "hourly"),
+            ("[policies.both.breaker]\nfailure_rate = 0.5\nwindow_calls = 20\nwindow_secs = 60", "both"),
+            ("[policies.instant.breaker]\nfailure_rate = 0.5\nwindow_secs = 0", "instant"),
+        ];
+        for (toml, name) in cases {
+            let error = ResiliencePolicies::from_toml(toml, &base()).unwrap_err();
+            assert!(matches!(error.root(), OmniXError::ConfigError(_)), "{}: {:?}", name, error);
+            assert!(error.to_string().contains(name), "{}", error);
+        }
+
+        assert!(ResiliencePolicies::from_toml("[policies.typo]\nmax_retires = 3", &base()).is_err(), "Unknown keys are errors");
+        assert!(ResiliencePolicies::from_toml("[policies.odd]\nbackoff = \"sometimes\"", &base()).is_err());
+        assert!(ResiliencePolicies::from_toml("", &OmniXErrorManagerConfig { max_retries: 0, ..base() }).is_err(), "The base is checked too");
+    }
+
+    #[test]
+    fn test_missing_file_yields_the_base_config() {
+        let policies = ResiliencePolicies::load(Path::new("config/does-not-exist.toml"), &base()).unwrap();
+        assert_eq!(policies.names().collect::<Vec<_>>(), vec![DEFAULT_POLICY]);
+    }
+
+    #[tokio::test]
+    async fn test_call_sites_select_policies_by_name() {
+        let policies = ResiliencePolicies::from_toml("[policies.stubborn]\nmax_retries = 5\nbase_delay_ms = 1", &base()).unwrap();
+        let manager = OmniXErrorManager::new(base()).with_policies(policies);
+        let metrics = OmniXMetry::detached();
+        let attempts = &AtomicUsize::new(0);
+        let failing = move || async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(OmniXError::NetworkError("unreachable".to_string()))
+        };
+
+        let result = manager.handle_operation_with_policy("stubborn", "db", failing, &metrics).await;
+        assert!(matches!(result, Err(OmniXError::RetryLimitExceeded { retries: 5, .. })));
+        assert_eq!(attempts.load(Ordering::SeqCst), 5);
+
+        let result = manager.handle_operation_with_policy("missing", "db", failing, &metrics).await;
+        assert!(matches!(result, Err(OmniXError::ConfigError(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 5, "Unknown policies run nothing");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_every_entry_point_accepts_a_policy() {
+        let policies = ResiliencePolicies::from_toml("[policies.stubborn]\nmax_retries = 5\nbase_delay_ms = 1", &base()).unwrap();
+        let manager = OmniXErrorManager::new(base()).with_policies(policies);
+        let metrics = OmniXMetry::detached();
+        let failing = || async { Err::<(), _>(OmniXError::NetworkError("unreachable".to_string())) };
+        let exhausted = |result: Result<(), OmniXError>| matches!(result, Err(OmniXError::RetryLimitExceeded { retries: 5, .. }));
+
+        let cancel = CancellationToken::new();
+        assert!(exhausted(manager.handle_operation_cancellable_with_policy("stubborn", "a", failing, &metrics, &cancel).await));
+        let hedge_after = Duration::from_secs(60);
+        assert!(exhausted(manager.handle_operation_hedged_with_policy("stubborn", "b", hedge_after, failing, &metrics).await));
+        let fell_back = manager
+            .handle_operation_with_policy_and_fallback("stubborn", "c", failing, |e| async move { Err(e) }, &metrics)
+            .await;
+        assert!(exhausted(fell_back));
+        let blocking = manager.handle_operation_blocking_with_policy("stubborn", "d", || {
+            Err::<(), _>(OmniXError::NetworkError("unreachable".to_string()))
+        }, &metrics);
+        assert!(exhausted(blocking));
+
+        let unknown = manager.handle_operation_blocking_with_policy("missing", "d", || Ok(()), &metrics);
+        assert!(matches!(unknown, Err(OmniXError::ConfigError(_))));
+        let unknown = manager.handle_operation_hedged_with_policy("missing", "b", hedge_after, failing, &metrics).await;
+        assert!(matches!(unknown, Err(OmniXError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_policy_file_changes_are_hot_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("resilience.toml");
+        std::fs::write(&path, "[policies.default]\nmax_retries = 2").unwrap();
+        let policies = ResiliencePolicies::load(&path, &base()).unwrap();
+        let manager = Arc::new(OmniXErrorManager::new(base()).with_policies(policies));
+        assert_eq!(manager.policy(DEFAULT_POLICY).unwrap().max_retries, 2);
+
+        let watcher = ResiliencePolicies::watch(manager.clone(), path.clone(), base(), Duration::from_millis(10));
+        std::fs::write(&path, "[policies.default]\nmax_retries = 7\n\n[policies.batch]\ntimeout_ms = 100").unwrap();
+        let reloaded = async {
+            while manager.policy("batch").is_none() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), reloaded).await.expect("policies were not reloaded");
+        assert_eq!(manager.policy(DEFAULT_POLICY).unwrap().max_retries, 7, "The default policy goes through update_config");
+
+        std::fs::write(&path, "[policies.default]\nmax_retries = 0").unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(manager.policy(DEFAULT_POLICY).unwrap().max_retries, 7, "Invalid edits are ignored");
+        watcher.abort();
+    }
 }
-"#, project_name, project_name)
+"##, project_name)
 }
 
 fn generate_utils_lxsl_tests_content(project_name: &str) -> String {
-    format!(r##"// tests/utils_lxsl_tests.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[TESTS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+    render_crate_template(r##"// tests/utils_lxsl_tests.rs ~=#######D]======A===r===c====M===o===o===n=====<Lord[UTILS-LXSL-TESTS]Xyn>=====S===t===u===d===i===o===s======[R|$>
 
 #[cfg(test)]
 mod tests {
-    use test6::utils::lxsl::LordXynSignatureLine;
+    use test6::utils::lxsl::{LordXynSignatureLine, LxslOutcome};
+    use test6::utils::langreg::LanguageRegistry;
+    use test6::utils::xyntag::{module_tag, TagPolicy};
     use std::io::{BufRead, Write};
+    use std::path::Path;
 
     #[test]
     fn test_generate_signature_line() {
@@ -1835,25 +6737,90 @@ mod tests {
     
 
     #[test]
-    fn test_build_signature_path() {
-        let path_parts = ["src", "utils", "lxsl.rs"];
-        let signature_path = LordXynSignatureLine::build_signature_path(&path_parts);
-        assert_eq!(signature_path, "src/utils/", "Signature path should be correctly built from path parts");
+    fn test_get_comment_prefix() {
+        assert_eq!(LordXynSignatureLine::get_comment_prefix("rs"), "//", "Comment prefix for Rust files should be '//'");
+        assert_eq!(LordXynSignatureLine::get_comment_prefix("py"), "#", "Comment prefix for Python files should be '#'");
+        assert_eq!(LordXynSignatureLine::get_comment_prefix("html"), "<!--", "Comment prefix for HTML files should be '<!--'");
+        assert_eq!(LordXynSignatureLine::get_comment_prefix("unknown"), "", "Comment prefix for unknown files should be empty");
     }
 
     #[test]
-    fn test_build_xyn_signature() {
-        let path_parts = ["src", "utils", "lxsl.rs"];
-        let xyn_signature = LordXynSignatureLine::build_xyn_signature(&path_parts);
-        assert_eq!(xyn_signature, "LXSL", "Xyn signature should be correctly generated from the file name");
+    fn test_block_comment_signatures_are_closed() {
+        let css_signature = LordXynSignatureLine::generate_signature_line("assets/site.css");
+        assert!(css_signature.starts_with("/* assets/site.css "), "CSS signature should open a block comment");
+        assert!(css_signature.ends_with("[R|$> */"), "CSS signature should close its block comment");
+
+        let html_signature = LordXynSignatureLine::generate_signature_line("index.html");
+        assert!(html_signature.starts_with("<!--") && html_signature.ends_with("-->"), "HTML signature should be a complete comment");
     }
 
     #[test]
-    fn test_get_comment_prefix() {
-        assert_eq!(LordXynSignatureLine::get_comment_prefix("rs"), "//", "Comment prefix for Rust files should be '//'");
-        assert_eq!(LordXynSignatureLine::get_comment_prefix("py"), "#", "Comment prefix for Python files should be '#'");
-        assert_eq!(LordXynSignatureLine::get_comment_prefix("html"), "<!--", "Comment prefix for HTML files should be '<!--'");
-        assert_eq!(LordXynSignatureLine::get_comment_prefix("unknown"), "", "Comment prefix for unknown files should be empty");
+    fn test_registry_lookup_by_file_name() {
+        let registry = LanguageRegistry::builtin();
+        assert_eq!(registry.lookup(Path::new("docker/Dockerfile")).map(|l| l.comment_start.as_str()), Some("#"));
+        assert_eq!(registry.lookup(Path::new("Makefile")).map(|l| l.name.as_str()), Some("make"));
+        assert!(registry.lookup(Path::new("Cargo.toml")).is_none(), "TOML files are never stamped");
+    }
+
+    #[test]
+    fn test_registry_user_additions_take_precedence() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_path = temp_dir.path().join("languages.toml");
+        std::fs::write(&config_path, "[[languages]]\nname = \"lua\"\nextensions = [\"lua\", \"txt\"]\ncomment_start = \"--[[\"\ncomment_end = \"]]\"\n").unwrap();
+
+        let registry = LanguageRegistry::load(&config_path).unwrap();
+        assert_eq!(registry.lookup(Path::new("init.lua")).map(|l| l.name.as_str()), Some("lua"));
+        assert_eq!(registry.lookup(Path::new("notes.txt")).map(|l| l.name.as_str()), Some("lua"));
+        assert_eq!(registry.lookup(Path::new("main.rs")).map(|l| l.name.as_str()), Some("rust"));
+    }
+
+    #[test]
+    fn test_signature_follows_shebang() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let script_path = temp_dir.path().join("run.sh");
+        std::fs::write(&script_path, "#!/bin/sh\necho hi\n").unwrap();
+
+        let report = LordXynSignatureLine::enforce_signatures(temp_dir.path()).unwrap();
+        assert_eq!(report.count(LxslOutcome::Stamped), 1);
+
+        let content = std::fs::read_to_string(&script_path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], "#!/bin/sh", "Shebang must stay on line 1");
+        assert!(lines[1].starts_with("# ") && lines[1].contains("<Lord[RUN]Xyn>"), "Signature should follow the shebang");
+        assert_eq!(lines[2], "echo hi");
+    }
+
+    #[test]
+    fn test_tag_policies() {
+        let omnixerror = Path::new("src/omnixtracker/omnixerror.rs");
+        assert_eq!(module_tag(omnixerror, TagPolicy::FileStem), "OMNIXERROR");
+        assert_eq!(module_tag(omnixerror, TagPolicy::ParentModule), "OMNIXTRACKER");
+        assert_eq!(module_tag(omnixerror, TagPolicy::ModulePath), "OMNIXTRACKER::OMNIXERROR");
+
+        let utils_mod = Path::new("src/utils/mod.rs");
+        assert_eq!(module_tag(utils_mod, TagPolicy::FileStem), "UTILS");
+        assert_eq!(module_tag(utils_mod, TagPolicy::ModulePath), "UTILS");
+
+        for policy in [TagPolicy::FileStem, TagPolicy::ParentModule, TagPolicy::ModulePath] {
+            assert_eq!(module_tag(Path::new("build.rs"), policy), "BUILD");
+            assert_eq!(module_tag(Path::new("src/main.rs"), policy), "MAIN");
+        }
+        assert_eq!(module_tag(Path::new("tests/utils_lxsl_tests.rs"), TagPolicy::ParentModule), "TESTS");
+        assert_eq!("module_path".parse::<TagPolicy>(), Ok(TagPolicy::ModulePath));
+    }
+
+    #[test]
+    fn test_signature_matches_generated_headers() {
+        // xynpro, build.rs/xtask and lxsl must all render the same first line
+        for file_path in ["build.rs", "src/main.rs", "src/lib.rs", "src/omnixtracker/omnixerror.rs", "src/utils/lxsl.rs", "src/utils/xyntag.rs"] {
+            let content = std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join(file_path)).unwrap();
+            let first_line = content.lines().next().unwrap();
+            assert_eq!(
+                first_line,
+                LordXynSignatureLine::generate_signature_line_with(file_path, TagPolicy::FileStem),
+                "Header of {} should match the lxsl signature", file_path
+            );
+        }
     }
 
     #[test]
@@ -1873,13 +6840,93 @@ mod tests {
         assert!(LordXynSignatureLine::should_skip_file("file.lock"), "File with .lock extension should be skipped");
         assert!(!LordXynSignatureLine::should_skip_file("file.rs"), "File with .rs extension should not be skipped");
     }
+
+    #[test]
+    fn test_enforce_signature_leaves_correct_header_untouched() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("lib.rs");
+        let file_path = file_path.to_str().unwrap();
+
+        std::fs::write(file_path, "fn main() {}\n").unwrap();
+        assert_eq!(LordXynSignatureLine::enforce_signature(file_path).unwrap(), LxslOutcome::Stamped);
+        assert_eq!(LordXynSignatureLine::enforce_signature(file_path).unwrap(), LxslOutcome::Unchanged);
+    }
+
+    #[test]
+    fn test_enforce_signatures_uses_cache_on_second_run() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        std::fs::write(temp_dir.path().join("src/one.rs"), "fn one() {}\n").unwrap();
+        std::fs::write(temp_dir.path().join("src/two.rs"), "fn two() {}\n").unwrap();
+        std::fs::write(temp_dir.path().join("Cargo.lock"), "# lock\n").unwrap();
+
+        let first = LordXynSignatureLine::enforce_signatures(temp_dir.path()).unwrap();
+        assert_eq!(first.count(LxslOutcome::Stamped), 2);
+        assert_eq!(first.count(LxslOutcome::Skipped), 1);
+        assert!(first.errors.is_empty());
+
+        let second = LordXynSignatureLine::enforce_signatures(temp_dir.path()).unwrap();
+        assert_eq!(second.count(LxslOutcome::Stamped), 0);
+        assert_eq!(second.count(LxslOutcome::Cached), 2);
+
+        let content = std::fs::read_to_string(temp_dir.path().join("src/one.rs")).unwrap();
+        assert!(content.starts_with("// src/one.rs "), "Signature should use the path relative to the walked root");
+    }
+
+    #[test]
+    fn test_languages_are_resolved_from_the_walked_root() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("config")).unwrap();
+        std::fs::write(
+            temp_dir.path().join("config/languages.toml"),
+            "[[languages]]\nname = \"lua\"\nextensions = [\"lua\"]\ncomment_start = \"--\"\n",
+        ).unwrap();
+        std::fs::write(temp_dir.path().join("init.lua"), "print(1)\n").unwrap();
+
+        let report = LordXynSignatureLine::enforce_signatures(temp_dir.path()).unwrap();
+        assert_eq!(report.count(LxslOutcome::Stamped), 1);
+        let content = std::fs::read_to_string(temp_dir.path().join("init.lua")).unwrap();
+        assert!(content.starts_with("-- init.lua ~=#######D]"), "Languages from the root's config should apply");
+    }
+
+    #[test]
+    fn test_is_binary_content() {
+        assert!(LordXynSignatureLine::is_binary_content(b"text\0with nul", true), "NUL bytes should mark content as binary");
+        assert!(LordXynSignatureLine::is_binary_content(&[0xff, 0xfe, 0x41], true), "Invalid UTF-8 should mark content as binary");
+        assert!(!LordXynSignatureLine::is_binary_content("caf\u{e9}".as_bytes(), true), "Valid UTF-8 should be text");
+        assert!(!LordXynSignatureLine::is_binary_content(&"\u{e9}".as_bytes()[..1], false), "Truncated character at the sniff boundary should be text");
+    }
+
+    #[test]
+    fn test_binary_file_is_reported_and_left_intact() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let binary_path = temp_dir.path().join("data.txt");
+        let binary_content = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00];
+        std::fs::write(&binary_path, &binary_content).unwrap();
+
+        let report = LordXynSignatureLine::enforce_signatures(temp_dir.path()).unwrap();
+        assert_eq!(report.count(LxslOutcome::SkippedBinary), 1);
+        assert!(report.to_string().contains("binary: 1, too large: 0"));
+        assert_eq!(std::fs::read(&binary_path).unwrap(), binary_content, "Binary file must not be modified");
+    }
+
+    #[test]
+    fn test_truncated_character_at_end_of_sniff_sized_file_is_binary() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("exact.txt");
+        let mut content = vec![b'a'; test6::constants::LXSL_SNIFF_BYTES - 1];
+        content.push(0xc3);
+        std::fs::write(&path, &content).unwrap();
+
+        assert_eq!(LordXynSignatureLine::sniff_file(&path).unwrap(), Some(LxslOutcome::SkippedBinary));
+    }
 }
 "##, project_name)
 }
 
 
 fn generate_xtls_xynpro_content() -> String {
-    r#"-- Xtls/XynPro_Instructions-HowTo.txt ~=#######D]======A===r===c====M===o===o===n=====<Lord[XTLS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+    r#"-- Xtls/XynPro_Instructions-HowTo.txt ~=#######D]======A===r===c====M===o===o===n=====<Lord[XYNPRO-INSTRUCTIONS-HOWTO]Xyn>=====S===t===u===d===i===o===s======[R|$>
 
 XynPro Instructions
 
@@ -1915,7 +6962,7 @@ Remember, if you need to make any further changes, you'll need to repeat steps 2
 }
 
 fn generate_xtls_xyntools_content() -> String {
-    r#"-- Xtls/XynTools-HowTo.txt ~=#######D]======A===r===c====M===o===o===n=====<Lord[XTLS]Xyn>=====S===t===u===d===i===o===s======[R|$>
+    r#"-- Xtls/XynTools-HowTo.txt ~=#######D]======A===r===c====M===o===o===n=====<Lord[XYNTOOLS-HOWTO]Xyn>=====S===t===u===d===i===o===s======[R|$>
 
 XynPro Command Guide
 
@@ -1933,6 +6980,10 @@ XynPro Command Guide
    - Clean existing signatures from files
    - Add new signatures to appropriate files
 
+   Projects generated with `xynpro <project_name> --xtask` never stamp headers from build.rs.
+   Run the stamping on demand instead:
+     $ cargo xtask stamp
+
 2. Xyn Git Update (XynGit)
    Command: xyngit
    Description: Automates the process of staging, committing, and pushing changes to the git repository.